@@ -7,19 +7,19 @@
 //! The module allows each user to create a match to bet on and to place bets in matches created by other users,
 //! through the following dispatchable functions: 
 //!
-//! * **create_match:** Passing as arguments the ID of the external match, and the odds,
+//! * **set_odds:** Passing as arguments the ID of the external match, and the odds,
 //! 	it creates a match on which to act as a bookmaker and let other users bet on this.
 //! * **place_bet:** Allows a user to bet on an open match. To do this, the user need to select the ID of the match
 //! 	on which bet on, the predicted result and the amount wagered. Once the transaction and the bet have been submitted,
 //! 	an amount equal to the bet one will be reserved in the bettor's account, an amount equal to the bet one multiplied
 //! 	by the established odds will be reserved in the bookmaker's account.
-//! * **set_match_result:** Retrieves the match result and saves it in storage. Subsequently, based on the latter,
-//! 	it scrolls all the bets related to that match and establishes the outcome, unreserving the entire amount of the bet
-//! 	to the winner (bettor or bookmaker). N.B.:
-//!     	* This call that can be made by any user at the moment, should be scheduled after the end of the event,
-//! 		saving the end-of-event timestamp among the match data.
-//!     	* The retrieval of a match result should be done through HTTP request using an ocw. To simplify this function,
-//! 		the RandomnessCollectiveFlip implementation of Randomness was used to generate the scores of the teams.
+//! * **set_match_result:** Submits the vote of a registered oracle for the match result, after the expected end
+//! 	of the match. Once `ResultQuorum` oracles agree on the score, the match is closed and its bets can be settled,
+//! 	unreserving the entire amount of each bet to the winner (bettor or bookmaker). N.B.:
+//!     	* Oracles are registered by root through *add_oracle* and *remove_oracle*, and call through the configured
+//! 		`OracleOrigin`.
+//!     	* The retrieval of a match result is done through HTTP request using an ocw. The *set_random_match_result* call
+//! 		uses the RandomnessCollectiveFlip implementation of Randomness to generate the scores of the teams.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -27,13 +27,17 @@
 mod mock;
 #[cfg(test)]
 mod tests;
+pub mod migrations;
+pub mod odds_format;
+pub mod runtime_api;
 
 use codec::{Decode, Encode};
 use frame_support::{
 	dispatch::{DispatchResult},
 	ensure,
 	pallet_prelude::*,
-	traits::{Currency, Get, ReservableCurrency, BalanceStatus, Randomness},
+	CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+	traits::{Currency, Get, ReservableCurrency, BalanceStatus, Randomness, OnUnbalanced, ConstU32, ExistenceRequirement, WithdrawReasons},
 	PalletId, RuntimeDebug,
 };
 use frame_system::{
@@ -52,11 +56,12 @@ use frame_support::sp_runtime::{
 		//storage::{MutateStorageError, StorageRetrievalError, StorageValueRef},
 		Duration,
 	},
-	traits::{Saturating},
+	traits::{AccountIdConversion, One, Saturating, UniqueSaturatedFrom, UniqueSaturatedInto, Zero},
 	//transaction_validity::{InvalidTransaction, TransactionValidity, ValidTransaction},
-	Percent,
+	FixedPointNumber, FixedU128, Permill,
 };
 use sp_std::prelude::*;
+use odds_format::{FormattedOdds, OddsFormat};
 //pub use weights::WeightInfo;
 
 /// Id of EventCategory es: Football League X, Football League Y, ESport Game X, Esport Game Y. 
@@ -68,23 +73,38 @@ pub type MatchId = (MatchCategoryId, MatchIntraCategoryId);
 pub type OddsId<T> = (MatchId, AccountIdOf<T>);
 /// An index of a Bet
 pub type BetIndex = u64;
+pub type VaultId = u32;
+/// Tag of the vault sub-accounts of the pallet, keeping them apart from its other accounts.
+const VAULT_ACCOUNT_TAG: &[u8; 5] = b"vault";
+pub type OrderId = u64;
+pub type MatchedBetIndex = u64;
+pub type AccumulatorIndex = u64;
+pub type SystemBetIndex = u64;
 type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
-type BetOf<T> = Bet<AccountIdOf<T>, BalanceOf<T>, OddsId<T>>;
-/// Odd touple composed by integer e fractional part through Percent
-type Odd = (u32, u8);
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::NegativeImbalance;
+type BetOf<T> = Bet<AccountIdOf<T>, BalanceOf<T>, OddsId<T>, <T as frame_system::Config>::BlockNumber>;
+type OrderOf<T> = Order<AccountIdOf<T>, BalanceOf<T>>;
+type MatchedBetOf<T> = MatchedBet<AccountIdOf<T>, BalanceOf<T>>;
+/// Decimal odd as fixed point number, es: 2.5 returns 2.5 times the stake, stake included.
+pub type Odd = FixedU128;
+/// Over/under line expressed in tenths of a goal, es: 25 is the 2.5 goals line.
+/// Only whole and half-goal lines are accepted.
+pub type GoalLine = u32;
 
 #[derive(
 	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Copy,
 )]
 
-/// A Match have an initial state (Open), and 2 final states (Closed, Postponed).
+/// A Match have an initial state (Locked), and 2 final states (Closed, Cancelled).
+/// A Postponed match can be rescheduled, going back to Open, or cancelled.
 pub enum MatchStatus {
 	#[default]
 	Locked,
 	Open,
 	Closed,
 	Postponed,
+	Cancelled,
 }
 
 #[derive(
@@ -96,6 +116,34 @@ pub struct Match {
 	pub home_score: u32,
 	pub away_score: u32,
 	pub timestamp_start: u64,
+	/// Expected end of the match, results cannot be submitted before it.
+	/// Set to `DefaultMatchDuration` after the start for the matches stored before it existed.
+	pub timestamp_end: u64,
+	/// The match was marked as finished before its expected end.
+	pub finished_early: bool,
+	/// End of the window in which the match result can be disputed, bets cannot be settled before it.
+	pub dispute_end: u64,
+}
+
+#[derive(
+	Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Clone,
+)]
+pub struct Dispute<AccountId, Balance> {
+	/// The account challenging the match result.
+	pub challenger: AccountId,
+	/// The amount reserved from the challenger, slashed if the result is confirmed.
+	pub bond: Balance,
+}
+
+#[derive(
+	Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Clone,
+)]
+/// Pooled bookmaker liquidity: the vault account posts odds and collateralises the bets on them.
+pub struct Vault<AccountId, Balance> {
+	/// The account posting the odds of the vault.
+	pub manager: AccountId,
+	/// Total shares of the depositors.
+	pub shares: Balance,
 }
 
 #[derive(
@@ -107,10 +155,39 @@ pub struct Odds {
 	pub draw: Odd,
 	pub under: Odd,
 	pub over: Odd,
+	/// The total goals line of the under and over odds, set to 3 goals for the odds stored before it existed.
+	pub line: GoalLine,
+	/// Timestamp in milliseconds from which the odds no longer accept bets, if any.
+	pub valid_until: Option<u64>,
+}
+
+impl Odds {
+	/// The odd offered for a prediction.
+	pub fn odd(&self, prediction: &Prediction) -> Odd {
+		match prediction {
+			Prediction::Homewin => self.homewin,
+			Prediction::Awaywin => self.awaywin,
+			Prediction::Draw => self.draw,
+			Prediction::Over => self.over,
+			Prediction::Under => self.under,
+		}
+	}
+}
+
+#[derive(
+	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Eq, Copy,
+)]
+/// Implied probability sum of the markets of some odds, es: 1.05 for a 5% overround.
+/// `None` for markets with less than two predictions allowed by the match category.
+pub struct OddsMargin {
+	/// Homewin, Awaywin and Draw market.
+	pub result: Option<FixedU128>,
+	/// Under and Over market.
+	pub total_goals: Option<FixedU128>,
 }
 
 #[derive(
-	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq,
+	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Eq, Copy,
 )]
 pub enum Prediction {
 	#[default]
@@ -121,6 +198,117 @@ pub enum Prediction {
 	Over,
 }
 
+impl Prediction {
+	/// All the predictions, in the order used to index an offer `Book`.
+	pub const ALL: [Prediction; 5] =
+		[Prediction::Homewin, Prediction::Awaywin, Prediction::Draw, Prediction::Under, Prediction::Over];
+
+	/// The markets of a match, each made of mutually exclusive predictions.
+	pub const MARKETS: [&'static [Prediction]; 2] =
+		[&[Prediction::Homewin, Prediction::Awaywin, Prediction::Draw], &[Prediction::Under, Prediction::Over]];
+
+	/// The market the prediction belongs to.
+	pub fn market(&self) -> &'static [Prediction] {
+		match self {
+			Prediction::Under | Prediction::Over => Self::MARKETS[1],
+			_ => Self::MARKETS[0],
+		}
+	}
+}
+
+/// Version of the odds of an offer, increased by every `set_odds`.
+pub type OddsVersion = u32;
+
+#[derive(
+	Encode, Decode, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Eq, Copy,
+)]
+/// The offer a bettor accepts when placing a bet, guarding against odds changed in the meantime.
+pub enum OddsGuard {
+	/// Only the given version of the odds.
+	Version(OddsVersion),
+	/// Any version offering at least the given odd on the prediction,
+	/// on the given over/under line for the Under and Over predictions.
+	MinOdd(Odd, GoalLine),
+}
+
+#[derive(
+	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Eq, Copy,
+)]
+/// Markets of an offer on which the bookmaker temporarily stopped accepting bets.
+pub struct Suspension {
+	/// The whole offer is suspended.
+	pub all: bool,
+	/// Predictions suspended individually, indexed by `Prediction`.
+	pub predictions: [bool; 5],
+}
+
+impl Suspension {
+	/// Whether bets on a prediction are not accepted.
+	pub fn is_suspended(&self, prediction: &Prediction) -> bool {
+		self.all || self.predictions[*prediction as usize]
+	}
+}
+
+/// Amount wagered and winnable amount of the open bets on an offer, indexed by `Prediction`.
+pub type Book<Balance> = [(Balance, Balance); 5];
+
+#[derive(
+	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Eq, Copy,
+)]
+/// The sport of a match category, restricting the markets that make sense for it.
+pub enum Sport {
+	#[default]
+	Football,
+	Tennis,
+	Basketball,
+	ESport,
+	Other,
+}
+
+impl Sport {
+	/// Whether a market makes sense for the sport: Draw is not a final result in tennis and basketball.
+	pub fn allows(&self, prediction: &Prediction) -> bool {
+		match (self, prediction) {
+			(Sport::Tennis, Prediction::Draw) | (Sport::Basketball, Prediction::Draw) => false,
+			_ => true,
+		}
+	}
+}
+
+#[derive(
+	Encode, Decode, CloneNoBound, RuntimeDebugNoBound, MaxEncodedLen, TypeInfo, PartialEqNoBound,
+)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+/// Rules shared by all the matches of a category.
+pub struct Category<T: Config> {
+	/// The name of the category, es: Football League X.
+	pub name: BoundedVec<u8, T::MaxCategoryNameLength>,
+	/// The sport of the category.
+	pub sport: Sport,
+	/// The markets bets can be placed on.
+	pub markets: BoundedVec<Prediction, ConstU32<5>>,
+	/// Default duration in milliseconds of the matches, 0 to use `DefaultMatchDuration`.
+	pub duration: u64,
+	/// The oracles allowed to feed the matches data, any registered oracle if empty.
+	pub oracles: BoundedVec<AccountIdOf<T>, T::MaxOracles>,
+	/// Disabled categories do not accept new odds and bets.
+	pub enabled: bool,
+}
+
+#[derive(
+	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Eq, Copy,
+)]
+/// Limits set by a bookmaker on the bets accepted by an offer, `None` for no limit.
+pub struct BetLimits<Balance> {
+	/// Maximum amount wagered by a single bet.
+	pub max_stake: Option<Balance>,
+	/// Maximum net loss of the bookmaker if a single outcome wins.
+	pub max_outcome_liability: Option<Balance>,
+	/// Maximum worst-case net loss of the bookmaker on the match.
+	pub max_match_liability: Option<Balance>,
+}
+
 #[derive(
 	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Copy,
 )]
@@ -129,12 +317,19 @@ pub enum BetStatus {
 	Open,
 	Lost,
 	Won,
+	/// The match was cancelled or the total goals landed exactly on the line,
+	/// stake and winnable amount returned to their owners.
+	Void,
+	/// Settled before the result at the cash-out price of the bookmaker.
+	CashedOut,
+	/// Cancelled by the bettor shortly after placing it, stake refunded minus the cancellation fee.
+	Cancelled,
 }
 
 #[derive(
 	Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq,
 )]
-pub struct Bet<AccountId, Balance, OddsId> {
+pub struct Bet<AccountId, Balance, OddsId, BlockNumber> {
 	/// The owner of the bet, bettor account.
 	pub owner: AccountId,
 	/// Reference to the match odds on which bet on.
@@ -143,10 +338,133 @@ pub struct Bet<AccountId, Balance, OddsId> {
 	pub prediction: Prediction,
 	/// Save Odd value at the moment of Bet (Odds could changhe).
 	pub odd: Odd,
+	/// Save the over/under line at the moment of Bet, 3 goals for the bets placed before it existed.
+	pub line: GoalLine,
 	/// The amount wagered.
 	pub amount: Balance,
 	/// The status of the bet
 	pub status: BetStatus,
+	/// Version of the odds accepted by the bet.
+	pub odds_version: OddsVersion,
+	/// Block in which the bet was placed, starting its cancellation period.
+	pub placed_at: BlockNumber,
+	/// Whether the bet can be cancelled, as allowed by the bookmaker when it was placed.
+	pub cancellable: bool,
+}
+
+#[derive(
+	Encode, Decode, Default, Clone, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Eq, Copy,
+)]
+/// Side of an exchange order.
+pub enum OrderSide {
+	/// Bet that the prediction comes true, risking the stake.
+	#[default]
+	Back,
+	/// Bet against the prediction, risking the winnable amount of the backer.
+	Lay,
+}
+
+impl OrderSide {
+	/// The side an order is matched against.
+	pub fn opposite(&self) -> OrderSide {
+		match self {
+			OrderSide::Back => OrderSide::Lay,
+			OrderSide::Lay => OrderSide::Back,
+		}
+	}
+}
+
+#[derive(
+	Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Clone,
+)]
+/// Unmatched amount of an exchange order, waiting on the order book of its match and prediction.
+pub struct Order<AccountId, Balance> {
+	/// The account posting the order.
+	pub owner: AccountId,
+	pub id_match: MatchId,
+	pub prediction: Prediction,
+	pub side: OrderSide,
+	/// The worst odd accepted: the minimum for a back order, the maximum for a lay order.
+	pub odd: Odd,
+	/// The over/under line, orders are only matched on the same line.
+	pub line: GoalLine,
+	/// The unmatched backer stake. A lay order reserves the winnable amount of this stake.
+	pub amount: Balance,
+}
+
+#[derive(
+	Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Clone,
+)]
+/// A back order matched with a lay order, both sides holding their risk in reserve until settlement.
+pub struct MatchedBet<AccountId, Balance> {
+	pub backer: AccountId,
+	pub layer: AccountId,
+	pub id_match: MatchId,
+	pub prediction: Prediction,
+	pub line: GoalLine,
+	/// The odd of the order already on the book.
+	pub odd: Odd,
+	/// The matched backer stake.
+	pub amount: Balance,
+	/// The status of the bet from the backer point of view.
+	pub status: BetStatus,
+}
+
+#[derive(
+	Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Clone, Copy,
+)]
+/// A selection of an accumulator, with the odd and line of the bookmaker at the moment of the bet.
+pub struct AccumulatorLeg {
+	pub id_match: MatchId,
+	pub prediction: Prediction,
+	pub odd: Odd,
+	pub line: GoalLine,
+}
+
+#[derive(
+	Encode, Decode, CloneNoBound, RuntimeDebugNoBound, MaxEncodedLen, TypeInfo, PartialEqNoBound,
+)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+/// A bet on several matches of a single bookmaker, winning only if every leg wins.
+pub struct Accumulator<T: Config> {
+	/// The owner of the bet, bettor account.
+	pub owner: AccountIdOf<T>,
+	/// The bookmaker of every leg.
+	pub bookmaker: AccountIdOf<T>,
+	/// The selections, on distinct matches.
+	pub legs: BoundedVec<AccumulatorLeg, T::MaxAccumulatorLegs>,
+	/// Combined odd, the product of the odds of the legs.
+	pub odd: Odd,
+	/// The amount wagered.
+	pub amount: BalanceOf<T>,
+	/// The status of the bet
+	pub status: BetStatus,
+}
+
+#[derive(
+	Encode, Decode, CloneNoBound, RuntimeDebugNoBound, MaxEncodedLen, TypeInfo, PartialEqNoBound,
+)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+/// A system bet: every combination of the selections with the chosen number of legs, each one placed
+/// as an accumulator line and settled independently.
+pub struct SystemBet<T: Config> {
+	/// The owner of the bet, bettor account.
+	pub owner: AccountIdOf<T>,
+	/// The amount wagered on each line.
+	pub line_amount: BalanceOf<T>,
+	/// The accumulators of the lines.
+	pub lines: BoundedVec<AccumulatorIndex, T::MaxSystemLines>,
+}
+
+#[derive(
+	Encode, Decode, Default, RuntimeDebug, MaxEncodedLen, TypeInfo, PartialEq, Clone, Copy,
+)]
+/// Parimutuel pool of a match: the stakes on each outcome of the 1X2 market, shared by the winners.
+pub struct Pool<Balance> {
+	/// Total stake on each outcome, indexed by `Prediction`.
+	pub stakes: [Balance; 3],
 }
 
 // Offchain worker
@@ -182,10 +500,15 @@ pub mod crypto {
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
+	use frame_support::{dispatch::GetDispatchInfo, traits::UnfilteredDispatchable};
 	use frame_system::pallet_prelude::*;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -201,6 +524,76 @@ pub mod pallet {
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
 		/// The identifier type for an offchain worker.
 		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// The origin allowed to feed match data (start time and result), the account must be also
+		/// registered into `Oracles`.
+		type OracleOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+		/// Maximum number of authorised oracle accounts.
+		#[pallet::constant]
+		type MaxOracles: Get<u32>;
+		/// Number of identical oracle submissions required to finalise a match result.
+		#[pallet::constant]
+		type ResultQuorum: Get<u32>;
+		/// Milliseconds after the result finalisation in which the result can be disputed.
+		#[pallet::constant]
+		type DisputePeriod: Get<u64>;
+		/// The amount reserved from an account disputing a match result.
+		#[pallet::constant]
+		type DisputeBond: Get<BalanceOf<Self>>;
+		/// The origin that confirms or replaces a disputed match result.
+		type DisputeResolutionOrigin: EnsureOrigin<Self::Origin>;
+		/// Handler for the bonds slashed from losing challengers.
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// Expected duration in milliseconds of a match, when not specified by the oracle.
+		#[pallet::constant]
+		type DefaultMatchDuration: Get<u64>;
+		/// Maximum length of a category name.
+		#[pallet::constant]
+		type MaxCategoryNameLength: Get<u32>;
+		/// Length in milliseconds of the buckets grouping the live odds by their `valid_until` timestamp.
+		#[pallet::constant]
+		type ExpiryBucket: Get<u64>;
+		/// Maximum number of live odds with a `valid_until` timestamp in a single bucket.
+		#[pallet::constant]
+		type MaxExpiringOdds: Get<u32>;
+		/// Minimum overround of each market of the odds, rejecting guaranteed arbitrages.
+		#[pallet::constant]
+		type MinOverround: Get<Permill>;
+		/// Maximum overround of each market of the odds.
+		#[pallet::constant]
+		type MaxOverround: Get<Permill>;
+		/// Maximum number of unmatched exchange orders per match, prediction and side.
+		#[pallet::constant]
+		type MaxOrdersPerBook: Get<u32>;
+		/// Minimum backer stake of an exchange order.
+		#[pallet::constant]
+		type MinOrderAmount: Get<BalanceOf<Self>>;
+		/// Maximum number of matches with unmatched exchange orders starting in a single bucket of `ExpiryBucket`.
+		#[pallet::constant]
+		type MaxLapsingBooks: Get<u32>;
+		/// Maximum number of bookmakers offering odds on a single match.
+		#[pallet::constant]
+		type MaxBookmakersPerMatch: Get<u32>;
+		/// Deposit reserved from a bookmaker for its place among the bookmakers of a match,
+		/// released once its odds are withdrawn or expired.
+		#[pallet::constant]
+		type OddsDeposit: Get<BalanceOf<Self>>;
+		/// Share of a parimutuel pool retained when paying the winners.
+		#[pallet::constant]
+		type PoolTake: Get<Permill>;
+		/// Handler for the take retained from the parimutuel pools.
+		type OnPoolTake: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// Maximum number of legs of an accumulator.
+		#[pallet::constant]
+		type MaxAccumulatorLegs: Get<u32>;
+		/// Maximum number of lines a system bet expands to.
+		#[pallet::constant]
+		type MaxSystemLines: Get<u32>;
+		/// Number of blocks after placement in which a bet can be cancelled.
+		#[pallet::constant]
+		type CancellationPeriod: Get<Self::BlockNumber>;
+		/// Share of the stake of a cancelled bet paid to the bookmaker.
+		#[pallet::constant]
+		type CancellationFee: Get<Permill>;
 	}
 
 	/// Mapping matches using match_index as key.
@@ -209,30 +602,303 @@ pub mod pallet {
 	pub(super) type Matches<T> =
 		StorageMap<_, Blake2_128Concat, MatchId, Match, OptionQuery>;
 
+	/// Match categories registry, managed by root.
+	#[pallet::storage]
+	#[pallet::getter(fn categories)]
+	pub(super) type Categories<T: Config> =
+		StorageMap<_, Blake2_128Concat, MatchCategoryId, Category<T>, OptionQuery>;
+
 	/// Mapping matches using match_index as key.
 	#[pallet::storage]
 	#[pallet::getter(fn odds)]
 	pub(super) type Odds<T> =
 		StorageMap<_, Blake2_128Concat, OddsId<T>, super::Odds, OptionQuery>;
 
+	/// Bookmakers that set odds on a match, in order of arrival.
+	#[pallet::storage]
+	#[pallet::getter(fn match_bookmakers)]
+	pub(super) type MatchBookmakers<T: Config> =
+		StorageMap<_, Blake2_128Concat, MatchId, BoundedVec<AccountIdOf<T>, T::MaxBookmakersPerMatch>, ValueQuery>;
+
+	/// Deposit held from the bookmaker of an offer for its place in `MatchBookmakers`.
+	#[pallet::storage]
+	#[pallet::getter(fn odds_deposit)]
+	pub(super) type OddsDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Offers withdrawn by their bookmaker, which cannot be set again.
+	#[pallet::storage]
+	pub(super) type WithdrawnOdds<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, (), OptionQuery>;
+
+	/// Offers whose bets cannot be cancelled, opted out by their bookmaker.
+	#[pallet::storage]
+	pub(super) type NonCancellableOdds<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, (), OptionQuery>;
+
+	/// Markets of an offer temporarily not accepting bets.
+	#[pallet::storage]
+	#[pallet::getter(fn suspension)]
+	pub(super) type Suspensions<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, Suspension, ValueQuery>;
+
+	/// Version of the live odds of an offer.
+	#[pallet::storage]
+	#[pallet::getter(fn odds_version)]
+	pub(super) type OddsVersions<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, OddsVersion, ValueQuery>;
+
+	/// The versions of the odds of an offer accepted by open bets, and the live one.
+	#[pallet::storage]
+	#[pallet::getter(fn odds_history)]
+	pub(super) type OddsHistory<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, OddsId<T>, Twox64Concat, OddsVersion, super::Odds, OptionQuery>;
+
+	/// Number of open bets on each version of the odds of an offer, keeping it in `OddsHistory`.
+	#[pallet::storage]
+	pub(super) type OddsVersionBets<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, OddsId<T>, Twox64Concat, OddsVersion, u32, ValueQuery>;
+
+	/// Live odds with a `valid_until` timestamp, by bucket of `ExpiryBucket` milliseconds,
+	/// pruned from `Odds` by `on_idle` once the bucket lapsed.
+	#[pallet::storage]
+	#[pallet::getter(fn expiring_odds)]
+	pub(super) type ExpiringOdds<T: Config> =
+		StorageMap<_, Twox64Concat, u64, BoundedVec<OddsId<T>, T::MaxExpiringOdds>, ValueQuery>;
+
+	/// First bucket of `ExpiringOdds` still to be pruned, unset until odds with a validity are stored.
+	#[pallet::storage]
+	#[pallet::getter(fn expiry_cursor)]
+	pub(super) type ExpiryCursor<T: Config> = StorageValue<_, u64, OptionQuery>;
+
+	/// Limits on the bets accepted by an offer, set by the bookmaker.
+	#[pallet::storage]
+	#[pallet::getter(fn bet_limits)]
+	pub(super) type OddsLimits<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, BetLimits<BalanceOf<T>>, ValueQuery>;
+
+	/// Winnable amount of the open bets on an offer, by predicted outcome.
+	#[pallet::storage]
+	#[pallet::getter(fn exposure)]
+	pub(super) type Exposure<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, OddsId<T>, Twox64Concat, Prediction, BalanceOf<T>, ValueQuery>;
+
+	/// Amount wagered by the open bets on an offer, by predicted outcome.
+	#[pallet::storage]
+	#[pallet::getter(fn stakes)]
+	pub(super) type Stakes<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, OddsId<T>, Twox64Concat, Prediction, BalanceOf<T>, ValueQuery>;
+
+	/// Over/under line of the open over/under bets on an offer, locking the line of its odds until they are settled.
+	#[pallet::storage]
+	#[pallet::getter(fn goal_line)]
+	pub(super) type GoalLines<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, GoalLine, OptionQuery>;
+
+	/// Amount wagered by the open bets accepted by a bookmaker, held in its reserve.
+	#[pallet::storage]
+	#[pallet::getter(fn open_stakes)]
+	pub(super) type OpenStakes<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Amount held in the bookmaker reserve for the open bets on an offer:
+	/// their stakes plus the worst-case net loss of the bookmaker.
+	#[pallet::storage]
+	#[pallet::getter(fn collateral)]
+	pub(super) type Collateral<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, BalanceOf<T>, ValueQuery>;
+
 	/// Mapping bets using bet_index as key.
 	#[pallet::storage]
 	#[pallet::getter(fn bets)]
 	pub(super) type Bets<T: Config> =
 		StorageMap<_, Blake2_128Concat, BetIndex, BetOf<T>, OptionQuery>;
 
-	/// A Storage Double Map of bets. Referenced by the id match to which it refers
-	/// (to quickly find all the bets related to a specific match), and its index.
-	// #[pallet::storage]
-	// #[pallet::getter(fn bets)]
-	// pub(super) type Bets<T: Config> =
-	// 	StorageDoubleMap<_, Blake2_128Concat, u32, Blake2_128Concat, BetIndex, BetInfoOf<T>, OptionQuery>;
+	/// Indexes of the bets placed on a match, by placement position
+	/// (to quickly find all the bets related to a specific match).
+	#[pallet::storage]
+	pub(super) type MatchBets<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, MatchId, Twox64Concat, u32, BetIndex, OptionQuery>;
+
+	/// Number of bets placed on a match, the next position in `MatchBets`.
+	#[pallet::storage]
+	#[pallet::getter(fn match_bet_count)]
+	pub(super) type MatchBetCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, MatchId, u32, ValueQuery>;
+
+	/// Position in `MatchBets` of the next bet to settle through bulk settlement.
+	#[pallet::storage]
+	#[pallet::getter(fn settlement_cursor)]
+	pub(super) type SettlementCursor<T: Config> =
+		StorageMap<_, Blake2_128Concat, MatchId, u32, ValueQuery>;
+
+	/// Closed or cancelled matches whose bets are still to be settled, processed by `on_idle`.
+	#[pallet::storage]
+	pub(super) type PendingSettlements<T: Config> =
+		StorageMap<_, Blake2_128Concat, MatchId, (), OptionQuery>;
+
+	/// Last match of `PendingSettlements` visited by `on_idle`, the next block resuming after it.
+	/// Unset when the next block starts from the first match.
+	#[pallet::storage]
+	#[pallet::getter(fn settlement_queue_cursor)]
+	pub(super) type SettlementQueueCursor<T: Config> = StorageValue<_, MatchId, OptionQuery>;
+
+	/// Indexes of the open bets placed by an account.
+	#[pallet::storage]
+	pub(super) type AccountBets<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, AccountIdOf<T>, Twox64Concat, BetIndex, (), OptionQuery>;
+
+	/// Matches on which a bookmaker holds collateral for open bets.
+	#[pallet::storage]
+	pub(super) type BookmakerMatches<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, AccountIdOf<T>, Blake2_128Concat, MatchId, (), OptionQuery>;
+
+	/// Indexes of the open bets accepted by a bookmaker.
+	#[pallet::storage]
+	pub(super) type BookmakerBets<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, AccountIdOf<T>, Twox64Concat, BetIndex, (), OptionQuery>;
 
 	/// Auto-incrementing bet counter
 	#[pallet::storage]
 	#[pallet::getter(fn bets_count)]
 	pub(super) type BetCount<T: Config> = StorageValue<_, BetIndex, ValueQuery>;
 
+	/// Bookmaker vaults.
+	#[pallet::storage]
+	#[pallet::getter(fn vaults)]
+	pub(super) type Vaults<T: Config> =
+		StorageMap<_, Blake2_128Concat, VaultId, Vault<AccountIdOf<T>, BalanceOf<T>>, OptionQuery>;
+
+	/// Auto-incrementing vault counter
+	#[pallet::storage]
+	#[pallet::getter(fn vaults_count)]
+	pub(super) type VaultCount<T: Config> = StorageValue<_, VaultId, ValueQuery>;
+
+	/// Shares of a vault owned by each depositor.
+	#[pallet::storage]
+	#[pallet::getter(fn vault_shares)]
+	pub(super) type VaultShares<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, VaultId, Blake2_128Concat, AccountIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Unmatched exchange orders.
+	#[pallet::storage]
+	#[pallet::getter(fn orders)]
+	pub(super) type Orders<T: Config> =
+		StorageMap<_, Blake2_128Concat, OrderId, OrderOf<T>, OptionQuery>;
+
+	/// Auto-incrementing order counter
+	#[pallet::storage]
+	#[pallet::getter(fn orders_count)]
+	pub(super) type OrderCount<T: Config> = StorageValue<_, OrderId, ValueQuery>;
+
+	/// Unmatched orders of a match by prediction and side, in price-time priority:
+	/// back orders by increasing odd, lay orders by decreasing odd.
+	#[pallet::storage]
+	#[pallet::getter(fn order_book)]
+	pub(super) type OrderBooks<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		MatchId,
+		Blake2_128Concat,
+		(Prediction, OrderSide),
+		BoundedVec<(Odd, OrderId), T::MaxOrdersPerBook>,
+		ValueQuery,
+	>;
+
+	/// Matches with unmatched orders, by bucket of `ExpiryBucket` milliseconds of their start,
+	/// whose orders are released by `on_idle` once the bucket lapsed.
+	#[pallet::storage]
+	#[pallet::getter(fn lapsing_books)]
+	pub(super) type LapsingBooks<T: Config> =
+		StorageMap<_, Twox64Concat, u64, BoundedVec<MatchId, T::MaxLapsingBooks>, ValueQuery>;
+
+	/// First bucket of `LapsingBooks` still to be released, unset until orders are left on a book.
+	#[pallet::storage]
+	#[pallet::getter(fn lapse_cursor)]
+	pub(super) type LapseCursor<T: Config> = StorageValue<_, u64, OptionQuery>;
+
+	/// Exchange bets made of a matched back and lay order.
+	#[pallet::storage]
+	#[pallet::getter(fn matched_bets)]
+	pub(super) type MatchedBets<T: Config> =
+		StorageMap<_, Blake2_128Concat, MatchedBetIndex, MatchedBetOf<T>, OptionQuery>;
+
+	/// Auto-incrementing matched bet counter
+	#[pallet::storage]
+	#[pallet::getter(fn matched_bets_count)]
+	pub(super) type MatchedBetCount<T: Config> = StorageValue<_, MatchedBetIndex, ValueQuery>;
+
+	/// Accumulator bets.
+	#[pallet::storage]
+	#[pallet::getter(fn accumulators)]
+	pub(super) type Accumulators<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccumulatorIndex, Accumulator<T>, OptionQuery>;
+
+	/// Auto-incrementing accumulator counter
+	#[pallet::storage]
+	#[pallet::getter(fn accumulators_count)]
+	pub(super) type AccumulatorCount<T: Config> = StorageValue<_, AccumulatorIndex, ValueQuery>;
+
+	/// System bets, made of accumulator lines.
+	#[pallet::storage]
+	#[pallet::getter(fn system_bets)]
+	pub(super) type SystemBets<T: Config> =
+		StorageMap<_, Blake2_128Concat, SystemBetIndex, SystemBet<T>, OptionQuery>;
+
+	/// Auto-incrementing system bet counter
+	#[pallet::storage]
+	#[pallet::getter(fn system_bets_count)]
+	pub(super) type SystemBetCount<T: Config> = StorageValue<_, SystemBetIndex, ValueQuery>;
+
+	/// Cash-out price offered by the bookmaker on an open bet.
+	#[pallet::storage]
+	#[pallet::getter(fn cash_out_offers)]
+	pub(super) type CashOutOffers<T: Config> =
+		StorageMap<_, Blake2_128Concat, BetIndex, BalanceOf<T>, OptionQuery>;
+
+	/// Margin of the cash-out price offered on every open bet on an offer, priced on its live odds.
+	#[pallet::storage]
+	#[pallet::getter(fn cash_out_margin)]
+	pub(super) type CashOutMargins<T: Config> =
+		StorageMap<_, Blake2_128Concat, OddsId<T>, Permill, OptionQuery>;
+
+	/// Parimutuel pools by match, their stakes held by the pallet account.
+	#[pallet::storage]
+	#[pallet::getter(fn pools)]
+	pub(super) type Pools<T: Config> =
+		StorageMap<_, Blake2_128Concat, MatchId, Pool<BalanceOf<T>>, OptionQuery>;
+
+	/// Stake of each account on the outcomes of a parimutuel pool, until claimed.
+	#[pallet::storage]
+	#[pallet::getter(fn pool_stakes)]
+	pub(super) type PoolStakes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		MatchId,
+		Blake2_128Concat,
+		(AccountIdOf<T>, Prediction),
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Accounts authorised to act as oracles, managed by root.
+	#[pallet::storage]
+	#[pallet::getter(fn oracles)]
+	pub(super) type Oracles<T: Config> =
+		StorageValue<_, BoundedVec<AccountIdOf<T>, T::MaxOracles>, ValueQuery>;
+
+	/// Scores submitted by the oracles for a match still waiting for the quorum.
+	#[pallet::storage]
+	#[pallet::getter(fn result_submissions)]
+	pub(super) type ResultSubmissions<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, MatchId, Blake2_128Concat, AccountIdOf<T>, (u32, u32), OptionQuery>;
+
+	/// Open disputes on match results, at most one per match.
+	#[pallet::storage]
+	#[pallet::getter(fn disputes)]
+	pub(super) type Disputes<T: Config> =
+		StorageMap<_, Blake2_128Concat, MatchId, Dispute<AccountIdOf<T>, BalanceOf<T>>, OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -242,12 +908,92 @@ pub mod pallet {
 		MatchOpened(MatchId),
 		/// Some Odds was created.
 		OddsCreated(OddsId<T>),
+		/// Some Odds was replaced by a new version.
+		OddsUpdated(OddsId<T>, OddsVersion),
+		/// Some Odds was withdrawn by the bookmaker.
+		OddsWithdrawn(OddsId<T>),
+		/// Some Odds lapsed at its `valid_until` timestamp and was removed.
+		OddsExpired(OddsId<T>),
+		/// A Vault was created: vault, manager.
+		VaultCreated(VaultId, AccountIdOf<T>),
+		/// An account deposited into a Vault: vault, depositor, amount, shares minted.
+		VaultDeposited(VaultId, AccountIdOf<T>, BalanceOf<T>, BalanceOf<T>),
+		/// An account withdrew from a Vault: vault, depositor, amount, shares burned.
+		VaultWithdrawn(VaultId, AccountIdOf<T>, BalanceOf<T>, BalanceOf<T>),
+		/// An exchange Order was placed, its unmatched amount left on the order book.
+		OrderPlaced(OrderId),
+		/// An exchange Order was removed from the order book, its unmatched amount released.
+		OrderCancelled(OrderId),
+		/// A back and a lay order were matched: matched bet, resting order, incoming order.
+		BetMatched(MatchedBetIndex, OrderId, OrderId),
+		/// A matched Bet was settled.
+		MatchedBetSettled(MatchedBetIndex),
+		/// A parimutuel Pool was opened on a match.
+		PoolOpened(MatchId),
+		/// A stake was added to a parimutuel Pool: match, bettor, prediction, amount.
+		PoolBetPlaced(MatchId, AccountIdOf<T>, Prediction, BalanceOf<T>),
+		/// The winnings of an account from a parimutuel Pool were paid out: match, bettor, amount.
+		PoolClaimed(MatchId, AccountIdOf<T>, BalanceOf<T>),
+		/// An Accumulator was placed.
+		AccumulatorPlaced(AccumulatorIndex),
+		/// An Accumulator was settled.
+		AccumulatorSettled(AccumulatorIndex),
+		/// A System bet was placed, expanded to accumulator lines.
+		SystemBetPlaced(SystemBetIndex),
+		/// The bookmaker set or removed the cash-out price of a Bet.
+		CashOutOffered(BetIndex, Option<BalanceOf<T>>),
+		/// The bookmaker set or removed the cash-out margin of its Odds.
+		CashOutMarginSet(OddsId<T>, Option<Permill>),
+		/// A Bet was cashed out: bet, amount paid to the bettor.
+		BetCashedOut(BetIndex, BalanceOf<T>),
+		/// The bookmaker allowed or disallowed the cancellation of the bets on its Odds.
+		BetCancellationSet(OddsId<T>, bool),
+		/// A Bet was cancelled: bet, fee paid to the bookmaker.
+		BetCancelled(BetIndex, BalanceOf<T>),
+		/// Some Odds stopped accepting bets on a prediction, or on every prediction if `None`.
+		OddsSuspended(OddsId<T>, Option<Prediction>),
+		/// Some Odds accepts bets again on a prediction, or on every prediction if `None`.
+		OddsResumed(OddsId<T>, Option<Prediction>),
 		/// A Bet was placed.
 		BetPlaced(BetIndex),
 		/// A Match was closed.
 		MatchClosed(MatchId),
 		/// A Match was closed.
 		BetSettled(BetIndex),
+		/// An account was authorised as oracle.
+		OracleAdded(AccountIdOf<T>),
+		/// An account was removed from the oracles.
+		OracleRemoved(AccountIdOf<T>),
+		/// An oracle submitted a match result: match, oracle, home score, away score.
+		MatchResultSubmitted(MatchId, AccountIdOf<T>, u32, u32),
+		/// An oracle submitted a match result different from the ones already submitted.
+		MatchResultConflict(MatchId, AccountIdOf<T>),
+		/// The quorum agreed on a match result: match, home score, away score.
+		MatchResultAgreed(MatchId, u32, u32),
+		/// A match result was disputed by an account.
+		MatchResultDisputed(MatchId, AccountIdOf<T>),
+		/// A dispute was rejected, the result is confirmed and the bond slashed.
+		DisputeRejected(MatchId),
+		/// A dispute was upheld, the result replaced with: home score, away score.
+		DisputeUpheld(MatchId, u32, u32),
+		/// A Match was postponed.
+		MatchPostponed(MatchId),
+		/// A postponed Match was rescheduled with a new start timestamp.
+		MatchRescheduled(MatchId, u64),
+		/// A Match was cancelled, its bets are void.
+		MatchCancelled(MatchId),
+		/// All the bets of a Match were settled.
+		MatchFullySettled(MatchId),
+		/// A Match was marked as finished before its expected end.
+		MatchFinishedEarly(MatchId),
+		/// A Category was created or updated.
+		CategorySet(MatchCategoryId),
+		/// A Category was enabled or disabled.
+		CategoryStatusChanged(MatchCategoryId, bool),
+		/// The bet limits of some Odds were set.
+		BetLimitsSet(OddsId<T>),
+		/// The automatic settlement of a Match failed, its bets are left to `settle_bet` and `settle_match`.
+		MatchSettlementFailed(MatchId),
 	}
 
 	#[pallet::error]
@@ -260,10 +1006,8 @@ pub mod pallet {
 		OddsNotExist,
 		/// Bet owner and match owner must be different.
 		SameMatchOwner,
-		/// Fractional part of the Odd out of bound, must be into <0...99> range.
-		OddFracPartOutOfBound,
-		/// Integer part of the Odd out of bound, must be 1 <= odd.0 <= 4_294_967_295u32.
-		OddIntPartOutOfBound,
+		/// Odd out of bound, must be greater than 1.
+		OddOutOfBound,
 		/// Match not open for bets or updates, functions available only on open matches.
 		MatchNotOpen,
 		/// Match open during bet settlement, cannot settle before match end.
@@ -280,10 +1024,155 @@ pub mod pallet {
 		BetSettled,
 		/// Payoff procedure failed.
 		PayoffError,
+		/// The caller is not a registered oracle, cannot feed match data.
+		NotOracle,
+		/// The account is already a registered oracle.
+		OracleAlreadyRegistered,
+		/// The account is not a registered oracle.
+		OracleNotRegistered,
+		/// Maximum number of oracles reached.
+		TooManyOracles,
+		/// The oracle already submitted this result for this match.
+		ResultAlreadySubmitted,
+		/// Match not closed, its result cannot be disputed.
+		MatchNotClosed,
+		/// The dispute period is over, the match result cannot be disputed anymore.
+		DisputePeriodOver,
+		/// The dispute period is not over, cannot settle bets on the match yet.
+		DisputePeriodNotOver,
+		/// The match result is disputed, cannot settle bets or dispute it again.
+		MatchDisputed,
+		/// A specific dispute does not exist.
+		DisputeNotExists,
+		/// Insufficient free-balance to post the dispute bond.
+		DisputeAccountInsufficientBalance,
+		/// Match postponed, cannot settle its bets until rescheduled and closed, or cancelled.
+		MatchPostponed,
+		/// Match not postponed, cannot be rescheduled.
+		MatchNotPostponed,
+		/// Match already closed or cancelled.
+		MatchFinalised,
+		/// The over/under line must be a whole or half-goal line, expressed in tenths of a goal.
+		InvalidGoalLine,
+		/// Match expected end not reached, cannot submit its result.
+		MatchNotFinished,
+		/// A specific category does not exist.
+		CategoryNotExists,
+		/// The category is disabled, cannot set odds or place bets on its matches.
+		CategoryDisabled,
+		/// The market is not allowed in the match category.
+		MarketNotAllowed,
+		/// The category markets include one that makes no sense for its sport.
+		InvalidCategoryMarkets,
+		/// The caller is not one of the oracles of the match category.
+		NotCategoryOracle,
+		/// The amount wagered is above the maximum stake accepted by the bookmaker.
+		StakeAboveLimit,
+		/// The bet would take the bookmaker liability on the outcome above its limit.
+		OutcomeLiabilityExceeded,
+		/// The bet would take the bookmaker liability on the match above its limit.
+		MatchLiabilityExceeded,
+		/// The over/under line cannot change while the odds have open over/under bets.
+		GoalLineLocked,
+		/// The live odds no longer match the version or the minimum odd accepted by the bettor.
+		OddsChanged,
+		/// The odds were withdrawn by the bookmaker and cannot be set again.
+		OddsWithdrawn,
+		/// The bookmaker suspended the odds on the prediction.
+		OddsSuspended,
+		/// The odds are no longer valid, or their validity ends in the past.
+		OddsExpired,
+		/// Maximum number of live odds with a `valid_until` timestamp reached.
+		TooManyExpiringOdds,
+		/// The implied probabilities of a market sum up to less than the minimum overround allows.
+		OverroundTooLow,
+		/// The implied probabilities of a market sum up to more than the maximum overround allows.
+		OverroundTooHigh,
+		/// A specific vault does not exist.
+		VaultNotExists,
+		/// The caller is not the manager of the vault.
+		NotVaultManager,
+		/// The vault lost all its funds, it cannot take deposits.
+		VaultInsolvent,
+		/// Not enough shares of the vault.
+		InsufficientShares,
+		/// The vault funds not exposed to open bets are not enough for the withdrawal.
+		VaultInsufficientFreeBalance,
+		/// A specific order does not exist.
+		OrderNotExists,
+		/// Only the order owner can cancel it before the match starts.
+		NotOrderOwner,
+		/// The order book of the prediction and side is full.
+		OrderBookFull,
+		/// A specific matched bet does not exist.
+		MatchedBetNotExists,
+		/// Maximum number of bookmakers on the match reached.
+		TooManyBookmakers,
+		/// No bookmaker can accept any part of the stake at the minimum odd.
+		NoOddsAvailable,
+		/// The match already has a parimutuel pool.
+		PoolAlreadyExists,
+		/// A specific parimutuel pool does not exist.
+		PoolNotExists,
+		/// Parimutuel pools only take bets on the 1X2 market.
+		PoolMarketNotAllowed,
+		/// The account has no stake to claim from the pool.
+		NothingToClaim,
+		/// An accumulator needs at least two legs.
+		AccumulatorTooShort,
+		/// The legs of an accumulator must be on distinct matches.
+		DuplicateLeg,
+		/// Maximum number of legs of an accumulator reached.
+		TooManyLegs,
+		/// A specific accumulator does not exist.
+		AccumulatorNotExists,
+		/// The legs of system bet lines must be at least two and at most the selections, in increasing order.
+		InvalidFoldSize,
+		/// The system bet expands to more than `MaxSystemLines` lines.
+		TooManySystemLines,
+		/// Only the bookmaker of the bet can offer a cash-out.
+		NotBetBookmaker,
+		/// Only the owner of the bet can cash it out.
+		NotBetOwner,
+		/// The cash-out price cannot exceed the stake plus the winnable amount.
+		CashOutAboveWinnable,
+		/// The bookmaker offers no cash-out on the bet.
+		NoCashOutOffer,
+		/// The cash-out price is below the minimum accepted by the bettor.
+		CashOutChanged,
+		/// The bookmaker does not allow to cancel the bets on its odds.
+		CancellationNotAllowed,
+		/// The cancellation period of the bet is over.
+		CancellationPeriodOver,
+		/// The stake of a bet cannot be zero.
+		ZeroStake,
+		/// Only the calls of a bookmaker on its odds and bets can be made on behalf of a vault.
+		NotVaultCall,
+		/// The vault has open bets on started matches, its shares cannot be priced until they are settled.
+		VaultSettling,
+		/// Odds with liability limits do not take accumulators.
+		LiabilityLimitedOdds,
+		/// The deposit is worth no shares of the vault.
+		ZeroShares,
+		/// Vault odds do not take accumulators.
+		VaultAccumulator,
+		/// The order stake is below the minimum order amount.
+		OrderBelowMinimum,
+		/// Too many matches with unmatched orders start in the same bucket.
+		TooManyLapsingBooks,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v1::migrate::<T>()
+				.saturating_add(migrations::v2::migrate::<T>())
+				.saturating_add(migrations::v3::migrate::<T>())
+				.saturating_add(migrations::v4::migrate::<T>())
+				.saturating_add(migrations::v5::migrate::<T>())
+				.saturating_add(migrations::v6::migrate::<T>())
+		}
+
 		/// Offchain Worker entry point.
 		fn offchain_worker(block_number: T::BlockNumber) {
 			
@@ -298,12 +1187,7 @@ pub mod pallet {
 						log::error!("Error: {}", e);
 					}
 				} else if itered_match.1.status == MatchStatus::Open {
-					let mut now = 0u64; // initialize
-					const MATCH_DURATION: u64 = 60000;
-					if let Ok(_timestamp) = Self::convert_moment_to_u64_in_milliseconds(<pallet_timestamp::Pallet<T>>::get()) {
-						now = _timestamp;
-					}
-					if (itered_match.1.timestamp_start + MATCH_DURATION) < now {
+					if itered_match.1.finished_early || itered_match.1.timestamp_end < Self::now() {
 						log::info!("Match {:?} should be over: try to retrieve its result", itered_match.0);
 						let res = Self::fetch_match_result_and_send_signed(itered_match.0);
 						if let Err(e) = res {
@@ -314,6 +1198,72 @@ pub mod pallet {
 			}
 
 		}
+
+		/// Use the spare block weight to prune the lapsed odds, to release the orders left on the books
+		/// of started matches and to settle the bets of closed and cancelled matches.
+		fn on_idle(_block_number: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let bet_weight = Self::settle_bet_weight();
+			// Reading the pending match, its state and its dispute.
+			let visit_weight = T::DbWeight::get().reads(3);
+			// Reading the bets and the cursor of a settleable match, then storing its cursor.
+			let match_weight = T::DbWeight::get().reads_writes(2, 2);
+			// Reading and storing the queue cursor.
+			let mut used_weight: Weight = Self::prune_expired_odds(remaining_weight);
+			used_weight = used_weight
+				.saturating_add(Self::release_lapsed_orders(remaining_weight.saturating_sub(used_weight)))
+				.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+			let mut pending = match Self::settlement_queue_cursor() {
+				Some(last) => <PendingSettlements<T>>::iter_keys_from(<PendingSettlements<T>>::hashed_key_for(last)),
+				None => <PendingSettlements<T>>::iter_keys(),
+			};
+			let mut last_visited = None;
+			let mut exhausted = false;
+			while used_weight.saturating_add(visit_weight) <= remaining_weight {
+				let id_match = match pending.next() {
+					Some(id_match) => id_match,
+					None => {
+						exhausted = true;
+						break
+					},
+				};
+				used_weight = used_weight.saturating_add(visit_weight);
+				// The dispute period might still be running, the match is visited again in the next round.
+				let selected_match = match Self::settleable_match(id_match) {
+					Ok(selected_match) => selected_match,
+					Err(_) => {
+						last_visited = Some(id_match);
+						continue
+					},
+				};
+				if used_weight.saturating_add(match_weight).saturating_add(bet_weight) > remaining_weight {
+					break
+				}
+				used_weight = used_weight.saturating_add(match_weight);
+				let max_bets = u32::try_from(remaining_weight.saturating_sub(used_weight) / bet_weight).unwrap_or(u32::MAX);
+				match frame_support::storage::with_storage_layer(|| Self::do_settle_match(id_match, &selected_match, max_bets)) {
+					Ok(visited) => used_weight = used_weight.saturating_add(bet_weight.saturating_mul(visited as Weight)),
+					Err(e) => {
+						log::error!("Unable to settle match {:?}: {:?}", id_match, e);
+						// Leave it to manual settlement, instead of retrying every block.
+						<PendingSettlements<T>>::remove(id_match);
+						Self::deposit_event(Event::MatchSettlementFailed(id_match));
+						used_weight = used_weight.saturating_add(bet_weight.saturating_mul(max_bets as Weight));
+					},
+				}
+				// A match partially settled is resumed by the next block.
+				if <PendingSettlements<T>>::contains_key(id_match) {
+					break
+				}
+				last_visited = Some(id_match);
+			}
+
+			if exhausted {
+				<SettlementQueueCursor<T>>::kill();
+			} else if let Some(last) = last_visited {
+				<SettlementQueueCursor<T>>::put(last);
+			}
+			used_weight
+		}
 	}
 
 
@@ -329,66 +1279,111 @@ pub mod pallet {
 		) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			let odds_owner = ensure_signed(origin)?;
-			// Check fractional part of Odd into <0...99> range. Due to the usage of Percent type we have to accept only values between 00 and 99.
-			ensure!(odds.homewin.1 < 99 && odds.awaywin.1 < 99 && odds.draw.1 < 99 && odds.under.1 < 99 && odds.over.1 < 99, Error::<T>::OddFracPartOutOfBound);
-			// Check integer part of Odd >= 1.
-			ensure!(odds.homewin.0 > 0 && odds.awaywin.0 > 0 && odds.draw.0 > 0 && odds.under.0 > 0 && odds.over.0 > 0, Error::<T>::OddIntPartOutOfBound);
-
-			// If the match is not in storage, add it.
-			if !<Matches<T>>::contains_key(id_match) {
-				let match_to_create = Match {
-					status: MatchStatus::Locked,
-					home_score: 0, 
-					away_score: 0,
-					timestamp_start: 0,
-				};
-				// Store the match with id_match as key.
-				<Matches<T>>::insert(id_match, match_to_create);
-				Self::deposit_event(Event::MatchCreated(id_match));
-			} else {
-				// Check match has not already started.
-				let match_to_book_on = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
-				let mut timestamp_u64 = 0u64; // initialize
-				if let Ok(_timestamp) = Self::convert_moment_to_u64_in_milliseconds(<pallet_timestamp::Pallet<T>>::get()) {
-					timestamp_u64 = _timestamp;
-				}
-				ensure!(match_to_book_on.timestamp_start > timestamp_u64, Error::<T>::MatchStarted);
-			}
-
-			//ensure!(!<Odds<T>>::contains_key((id_match, odds_owner.clone())), Error::<T>::OddIntPartOutOfBound); comment it if accept update of odds
-			<Odds<T>>::insert((id_match, odds_owner.clone()), odds);
-
-			Self::deposit_event(Event::OddsCreated((id_match, odds_owner)));
-			Ok(())
+			Self::do_set_odds(odds_owner, id_match, odds)
 		}
 
-		/// Saves the match result into storage. At the moment the results are generated randomly,
-		/// in future developments it can be called by the oracle.
+		/// Sets the limits on the bets accepted by the caller odds on a match:
+		/// maximum stake per bet, maximum liability per outcome and per match.
 		#[pallet::weight(10_000)]
-		pub fn set_match_start(
+		pub fn set_bet_limits(
 			origin: OriginFor<T>,
 			id_match: MatchId,
-			timestamp_start: u64,
+			limits: BetLimits<BalanceOf<T>>,
 		) -> DispatchResult {
-			let _who = ensure_signed(origin)?;
-			let mut selected_match = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
-			// Check if match is locked.
-			ensure!(selected_match.status == MatchStatus::Locked, Error::<T>::MatchNotLocked);
-			// Update match status and results.
-			// todo: randomize also MatchStatus.
-			selected_match.status = MatchStatus::Open;
-			selected_match.timestamp_start = timestamp_start;
-			<Matches<T>>::insert(id_match, selected_match);
-			// todo: maybe can try also this way: <Matches<T>>::try_mutate, instead of insert.
-			
-			Self::deposit_event(Event::MatchOpened(id_match));
-			Ok(().into())
+			let odds_owner = ensure_signed(origin)?;
+			let id_odds = (id_match, odds_owner);
+			ensure!(<Odds<T>>::contains_key(&id_odds), Error::<T>::OddsNotExist);
+
+			<OddsLimits<T>>::insert(&id_odds, limits);
+			Self::deposit_event(Event::BetLimitsSet(id_odds));
+			Ok(())
+		}
+
+		/// Permanently withdraws the caller odds on a match: no more bets are accepted,
+		/// the bets already placed stay valid and are settled as usual.
+		#[pallet::weight(10_000)]
+		pub fn withdraw_odds(origin: OriginFor<T>, id_match: MatchId) -> DispatchResult {
+			let odds_owner = ensure_signed(origin)?;
+			let id_odds = (id_match, odds_owner);
+			let odds = Self::odds(&id_odds).ok_or(Error::<T>::OddsNotExist)?;
+
+			Self::schedule_expiry(&id_odds, odds.valid_until, None)?;
+			Self::remove_offer(&id_odds);
+			<WithdrawnOdds<T>>::insert(&id_odds, ());
+			Self::deposit_event(Event::OddsWithdrawn(id_odds));
+			Ok(())
+		}
+
+		/// Temporarily stops accepting bets on a prediction of the caller odds on a match,
+		/// or on every prediction if `prediction` is `None`.
+		#[pallet::weight(10_000)]
+		pub fn suspend_odds(origin: OriginFor<T>, id_match: MatchId, prediction: Option<Prediction>) -> DispatchResult {
+			let odds_owner = ensure_signed(origin)?;
+			let id_odds = (id_match, odds_owner);
+			ensure!(<Odds<T>>::contains_key(&id_odds), Error::<T>::OddsNotExist);
+
+			<Suspensions<T>>::mutate(&id_odds, |suspension| match prediction {
+				Some(prediction) => suspension.predictions[prediction as usize] = true,
+				None => suspension.all = true,
+			});
+			Self::deposit_event(Event::OddsSuspended(id_odds, prediction));
+			Ok(())
+		}
+
+		/// Accepts again bets on a prediction of the caller odds on a match suspended individually,
+		/// or on the whole odds if `prediction` is `None`.
+		#[pallet::weight(10_000)]
+		pub fn resume_odds(origin: OriginFor<T>, id_match: MatchId, prediction: Option<Prediction>) -> DispatchResult {
+			let odds_owner = ensure_signed(origin)?;
+			let id_odds = (id_match, odds_owner);
+			ensure!(<Odds<T>>::contains_key(&id_odds), Error::<T>::OddsNotExist);
+
+			let mut suspension = Self::suspension(&id_odds);
+			match prediction {
+				Some(prediction) => suspension.predictions[prediction as usize] = false,
+				None => suspension.all = false,
+			}
+			if suspension == Suspension::default() {
+				<Suspensions<T>>::remove(&id_odds);
+			} else {
+				<Suspensions<T>>::insert(&id_odds, suspension);
+			}
+			Self::deposit_event(Event::OddsResumed(id_odds, prediction));
+			Ok(())
+		}
+
+		/// Opens the match to bets until its start timestamp. The expected end of the match is
+		/// given by its `duration` in milliseconds, or `DefaultMatchDuration` if not specified.
+		#[pallet::weight(10_000)]
+		pub fn set_match_start(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+			timestamp_start: u64,
+			duration: Option<u64>,
+		) -> DispatchResult {
+			let _who = Self::ensure_match_oracle(origin, id_match)?;
+			let mut selected_match = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+			// Check if match is locked.
+			ensure!(selected_match.status == MatchStatus::Locked, Error::<T>::MatchNotLocked);
+			// Update match status and results.
+			// todo: randomize also MatchStatus.
+			selected_match.status = MatchStatus::Open;
+			selected_match.timestamp_start = timestamp_start;
+			let duration = duration
+				.or_else(|| Self::categories(id_match.0).map(|category| category.duration).filter(|duration| *duration > 0))
+				.unwrap_or_else(T::DefaultMatchDuration::get);
+			selected_match.timestamp_end = timestamp_start.saturating_add(duration);
+			<Matches<T>>::insert(id_match, selected_match);
+			// todo: maybe can try also this way: <Matches<T>>::try_mutate, instead of insert.
+			
+			Self::deposit_event(Event::MatchOpened(id_match));
+			Ok(().into())
 		}
 
 		/// Allows a user to bet on an open match. To do this, the user need to select the ID of the match
 		/// on which bet on, the predicted result and the amount wagered. Once the transaction and the bet have been submitted,
-		/// an amount equal to the bet one will be reserved in the bettor's account, an amount equal to the bet one multiplied
-		/// by the established odds will be reserved in the bookmaker's account.
+		/// the amount wagered is moved to the bookmaker's reserve, adjusted to cover its worst-case net loss.
+		/// The bet fails with `OddsChanged` if the odds are no longer the version, or the minimum odd, `expected` by the bettor.
 		#[pallet::weight(10_000)]
 		pub fn place_bet(
 			origin: OriginFor<T>,
@@ -396,150 +1391,1016 @@ pub mod pallet {
 			odds_owner: AccountIdOf<T>,
 			prediction: Prediction,
 			amount: BalanceOf<T>,
+			expected: OddsGuard,
 		) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			let bet_owner = ensure_signed(origin)?;
-			let bet_index = BetCount::<T>::get();
-			// Retrieve the match struct and match_owner
-			let odds = Self::odds((id_match, odds_owner.clone())).ok_or(Error::<T>::OddsNotExist)?;
+			Self::do_place_bet(bet_owner, id_match, odds_owner, prediction, amount, expected)
+		}
+
+		/// Places a bet on the best odds of a match, splitting the stake across the bookmakers offering
+		/// at least `min_odd` on the prediction, best odd first, as far as each can cover.
+		/// Under and Over bets only go to the offers on the over/under `line`.
+		/// Records a bet for each fill, leaving unfilled the part of the stake no bookmaker can accept.
+		#[pallet::weight(
+			Pallet::<T>::fill_search_weight()
+				.saturating_add(Pallet::<T>::place_bet_weight())
+				.saturating_mul(T::MaxBookmakersPerMatch::get() as Weight)
+				.saturating_add(T::DbWeight::get().reads(2))
+		)]
+		pub fn place_bet_best(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+			prediction: Prediction,
+			amount: BalanceOf<T>,
+			min_odd: Odd,
+			line: GoalLine,
+		) -> DispatchResultWithPostInfo {
+			let bet_owner = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroStake);
+			ensure!(T::Currency::can_reserve(&bet_owner, amount), Error::<T>::BetAccountInsufficientBalance);
+			let now = Self::now();
+			// Check the match takes bets before ranking the offers.
 			let match_to_bet_on = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
-			// Ensure bet owner and match owner are not the same account.
-			ensure!(bet_owner != odds_owner.clone(), Error::<T>::SameMatchOwner);
-			// Ensure match is open.
 			ensure!(match_to_bet_on.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
-			// Ensure that the bet takes place before the match starts.
-			let timestamp: <T as pallet_timestamp::Config>::Moment = <pallet_timestamp::Pallet<T>>::get();
-			let mut timestamp_u64 = 0u64; // initialize
-			if let Ok(_timestamp) = Self::convert_moment_to_u64_in_milliseconds(timestamp) {
-				timestamp_u64 = _timestamp;
-			}
-			ensure!(match_to_bet_on.timestamp_start > timestamp_u64, Error::<T>::MatchStarted);
-			// Ensure that bettor account have suffient free balance.
-			ensure!(T::Currency::can_reserve(&bet_owner, amount), Error::<T>::BetAccountInsufficientBalance);
+			ensure!(match_to_bet_on.timestamp_start > now, Error::<T>::MatchStarted);
+			let bookmakers = Self::match_bookmakers(id_match);
+			let searched = bookmakers.len() as Weight;
+			let mut offers: Vec<(Odd, AccountIdOf<T>)> = bookmakers
+				.into_iter()
+				.filter(|bookmaker| *bookmaker != bet_owner)
+				.filter_map(|bookmaker| {
+					let id_odds = (id_match, bookmaker.clone());
+					let odds = Self::odds(&id_odds)?;
+					let odd = odds.odd(&prediction);
+					let available = odd >= min_odd &&
+						(!Prediction::MARKETS[1].contains(&prediction) || odds.line == line) &&
+						odds.valid_until.map_or(true, |valid_until| valid_until > now) &&
+						!Self::suspension(&id_odds).is_suspended(&prediction);
+					available.then(|| (odd, bookmaker))
+				})
+				.collect();
+			// Best odds first, the earliest bookmaker first on the same odd.
+			offers.sort_by(|a, b| b.0.cmp(&a.0));
 
-			let odd: Odd = match prediction {
-				Prediction::Homewin => odds.homewin,
-				Prediction::Awaywin => odds.awaywin,
-				Prediction::Draw => odds.draw,
-				Prediction::Over => odds.over,
-				Prediction::Under => odds.under,
-			};
-
-			let winnable_amount = (Percent::from_percent(odd.1) * amount).saturating_add(amount.saturating_mul(((odd.0 - 1) as u32).into()));
-			// Ensure that bookie account have suffient free balance.
-			ensure!(T::Currency::can_reserve(&odds_owner, winnable_amount), Error::<T>::OddsAccountInsufficientBalance);
-			T::Currency::reserve(&bet_owner, amount)?;
-			T::Currency::reserve(&odds_owner, winnable_amount)?;
-
-			let bet = Bet {
-				owner: bet_owner,
-				id_odds: (id_match, odds_owner),
-				prediction,
-				odd,
-				amount,
-				status: BetStatus::Open,
-			};
-			
-			// Insert bet into storage.
-			<Bets<T>>::insert(bet_index, bet);
-			// Not protected against overflow.
-			BetCount::<T>::put(bet_index + 1);
+			let mut remaining = amount;
+			let mut placed: Weight = 0;
+			for (odd, bookmaker) in offers {
+				if remaining.is_zero() {
+					break;
+				}
+				let fill = Self::max_fill(&(id_match, bookmaker.clone()), prediction, odd, remaining);
+				if fill.is_zero() {
+					continue;
+				}
+				Self::do_place_bet(bet_owner.clone(), id_match, bookmaker, prediction, fill, OddsGuard::MinOdd(odd, line))?;
+				remaining = remaining.saturating_sub(fill);
+				placed = placed.saturating_add(1);
+			}
+			ensure!(remaining < amount, Error::<T>::NoOddsAvailable);
 
-			// Emit Event
-			Self::deposit_event(Event::BetPlaced(bet_index));
-			Ok(().into())
+			let actual_weight = Self::fill_search_weight()
+				.saturating_mul(searched)
+				.saturating_add(Self::place_bet_weight().saturating_mul(placed))
+				.saturating_add(T::DbWeight::get().reads(2));
+			Ok(Some(actual_weight).into())
 		}
 
-		/// Saves the match result into storage. At the moment the results are generated randomly,
-		/// in future developments it can be called by the oracle.
+		/// Submits the oracle vote for the match result. The match is closed with the submitted score
+		/// once `ResultQuorum` oracles agree on it.
 		#[pallet::weight(10_000)]
 		pub fn set_match_result(
 			origin: OriginFor<T>,
 			id_match: MatchId,
 			home_score: u32,
 			away_score: u32,
+		) -> DispatchResult {
+			let who = Self::ensure_match_oracle(origin, id_match)?;
+			Self::submit_match_result(who, id_match, home_score, away_score)
+		}
+
+		/// Settles a bet, unlocking all funds towards the winner.
+		#[pallet::weight(10_000)]
+		pub fn settle_bet(
+			origin: OriginFor<T>,
+			id_bet: BetIndex,
 		) -> DispatchResult {
 			let _who = ensure_signed(origin)?;
-			let mut selected_match = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
-			// Check if match is open.
+			let bet = Self::bets(id_bet).ok_or(Error::<T>::BetNotExists)?;
+			// Check if bet is open.
+			ensure!(bet.status == BetStatus::Open, Error::<T>::BetSettled);
+			let selected_match = Self::settleable_match(bet.id_odds.0)?;
+			Self::do_settle_bet(id_bet, bet, &selected_match)
+		}
+
+		/// Settles up to `max_bets` bets of a closed or cancelled match, resuming from the last
+		/// settled position. Emits `MatchFullySettled` once every bet of the match is settled.
+		#[pallet::weight(
+			Pallet::<T>::settle_bet_weight()
+				.saturating_mul(*max_bets as Weight)
+				.saturating_add(T::DbWeight::get().reads_writes(3, 2))
+		)]
+		pub fn settle_match(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+			max_bets: u32,
+		) -> DispatchResultWithPostInfo {
+			let _who = ensure_signed(origin)?;
+			let selected_match = Self::settleable_match(id_match)?;
+			let visited = Self::do_settle_match(id_match, &selected_match, max_bets)?;
+
+			let actual_weight = Self::settle_bet_weight()
+				.saturating_mul(visited as Weight)
+				.saturating_add(T::DbWeight::get().reads_writes(3, 2));
+			Ok(Some(actual_weight).into())
+		}
+
+		/// Submits a randomly generated match result as oracle vote, useful for testing purposes.
+		#[pallet::weight(10_000)]
+		pub fn set_random_match_result(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+		) -> DispatchResult {
+			let who = Self::ensure_match_oracle(origin, id_match)?;
+			// todo: randomize also MatchStatus.
+			Self::submit_match_result(who, id_match, Self::generate_random_score(0), Self::generate_random_score(1))
+		}
+
+		/// Marks an open match as finished before its expected end, allowing its result to be submitted.
+		#[pallet::weight(10_000)]
+		pub fn mark_match_finished(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+		) -> DispatchResult {
+			let _who = Self::ensure_match_oracle(origin, id_match)?;
+			<Matches<T>>::try_mutate(id_match, |maybe_match| -> DispatchResult {
+				let selected_match = maybe_match.as_mut().ok_or(Error::<T>::MatchNotExists)?;
+				ensure!(selected_match.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
+				selected_match.finished_early = true;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::MatchFinishedEarly(id_match));
+			Ok(())
+		}
+
+		/// Postpones an open match, no bet can be placed or settled on it until it is rescheduled.
+		#[pallet::weight(10_000)]
+		pub fn postpone_match(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+		) -> DispatchResult {
+			let _who = Self::ensure_match_oracle(origin, id_match)?;
+			<Matches<T>>::try_mutate(id_match, |maybe_match| -> DispatchResult {
+				let selected_match = maybe_match.as_mut().ok_or(Error::<T>::MatchNotExists)?;
+				ensure!(selected_match.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
+				selected_match.status = MatchStatus::Postponed;
+				Ok(())
+			})?;
+			// Votes submitted for the original schedule are discarded.
+			let _ = <ResultSubmissions<T>>::clear_prefix(id_match, u32::MAX, None);
+
+			Self::deposit_event(Event::MatchPostponed(id_match));
+			Ok(())
+		}
+
+		/// Reopens a postponed match with its new start timestamp.
+		#[pallet::weight(10_000)]
+		pub fn reschedule_match(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+			timestamp_start: u64,
+		) -> DispatchResult {
+			let _who = Self::ensure_match_oracle(origin, id_match)?;
+			<Matches<T>>::try_mutate(id_match, |maybe_match| -> DispatchResult {
+				let selected_match = maybe_match.as_mut().ok_or(Error::<T>::MatchNotExists)?;
+				ensure!(selected_match.status == MatchStatus::Postponed, Error::<T>::MatchNotPostponed);
+				// The match keeps its expected duration.
+				let duration = selected_match.timestamp_end.saturating_sub(selected_match.timestamp_start);
+				selected_match.status = MatchStatus::Open;
+				selected_match.timestamp_start = timestamp_start;
+				selected_match.timestamp_end = timestamp_start.saturating_add(duration);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::MatchRescheduled(id_match, timestamp_start));
+			Ok(())
+		}
+
+		/// Cancels a match not yet closed, all its bets become void and their reserves are released on settlement.
+		#[pallet::weight(10_000)]
+		pub fn cancel_match(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+		) -> DispatchResult {
+			let _who = Self::ensure_match_oracle(origin, id_match)?;
+			<Matches<T>>::try_mutate(id_match, |maybe_match| -> DispatchResult {
+				let selected_match = maybe_match.as_mut().ok_or(Error::<T>::MatchNotExists)?;
+				ensure!(
+					selected_match.status != MatchStatus::Closed && selected_match.status != MatchStatus::Cancelled,
+					Error::<T>::MatchFinalised
+				);
+				selected_match.status = MatchStatus::Cancelled;
+				Ok(())
+			})?;
+			let _ = <ResultSubmissions<T>>::clear_prefix(id_match, u32::MAX, None);
+			<PendingSettlements<T>>::insert(id_match, ());
+
+			Self::deposit_event(Event::MatchCancelled(id_match));
+			Ok(())
+		}
+
+		/// Disputes the result of a closed match during the dispute period, reserving `DisputeBond`
+		/// from the challenger. The bets on the match cannot be settled until the dispute is resolved.
+		#[pallet::weight(10_000)]
+		pub fn dispute_match_result(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+		) -> DispatchResult {
+			let challenger = ensure_signed(origin)?;
+			let selected_match = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+			ensure!(selected_match.status == MatchStatus::Closed, Error::<T>::MatchNotClosed);
+			ensure!(Self::now() < selected_match.dispute_end, Error::<T>::DisputePeriodOver);
+			ensure!(!<Disputes<T>>::contains_key(id_match), Error::<T>::MatchDisputed);
+
+			let bond = T::DisputeBond::get();
+			ensure!(T::Currency::can_reserve(&challenger, bond), Error::<T>::DisputeAccountInsufficientBalance);
+			T::Currency::reserve(&challenger, bond)?;
+			<Disputes<T>>::insert(id_match, Dispute { challenger: challenger.clone(), bond });
+
+			Self::deposit_event(Event::MatchResultDisputed(id_match, challenger));
+			Ok(())
+		}
+
+		/// Resolves the dispute on a match result. Passing `None` confirms the result and slashes the
+		/// challenger bond, otherwise the result is replaced and the bond returned to the challenger.
+		/// A replaced result can be disputed in turn, during a new `DisputePeriod`.
+		#[pallet::weight(10_000)]
+		pub fn resolve_dispute(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+			new_result: Option<(u32, u32)>,
+		) -> DispatchResult {
+			T::DisputeResolutionOrigin::ensure_origin(origin)?;
+			let dispute = Self::disputes(id_match).ok_or(Error::<T>::DisputeNotExists)?;
+
+			match new_result {
+				None => {
+					let (imbalance, _) = T::Currency::slash_reserved(&dispute.challenger, dispute.bond);
+					T::Slash::on_unbalanced(imbalance);
+					Self::deposit_event(Event::DisputeRejected(id_match));
+				},
+				Some((home_score, away_score)) => {
+					<Matches<T>>::try_mutate(id_match, |maybe_match| -> DispatchResult {
+						let selected_match = maybe_match.as_mut().ok_or(Error::<T>::MatchNotExists)?;
+						ensure!(selected_match.status == MatchStatus::Closed, Error::<T>::MatchNotClosed);
+						selected_match.home_score = home_score;
+						selected_match.away_score = away_score;
+						selected_match.dispute_end = Self::now().saturating_add(T::DisputePeriod::get());
+						Ok(())
+					})?;
+					T::Currency::unreserve(&dispute.challenger, dispute.bond);
+					Self::deposit_event(Event::DisputeUpheld(id_match, home_score, away_score));
+				},
+			}
+			<Disputes<T>>::remove(id_match);
+			Ok(())
+		}
+
+		/// Creates or updates a match category. Root only.
+		#[pallet::weight(10_000)]
+		pub fn set_category(
+			origin: OriginFor<T>,
+			id_category: MatchCategoryId,
+			category: Category<T>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				category.markets.iter().all(|prediction| category.sport.allows(prediction)),
+				Error::<T>::InvalidCategoryMarkets
+			);
+			<Categories<T>>::insert(id_category, category);
+
+			Self::deposit_event(Event::CategorySet(id_category));
+			Ok(())
+		}
+
+		/// Enables or disables a match category. Root only.
+		#[pallet::weight(10_000)]
+		pub fn set_category_enabled(
+			origin: OriginFor<T>,
+			id_category: MatchCategoryId,
+			enabled: bool,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<Categories<T>>::try_mutate(id_category, |maybe_category| -> DispatchResult {
+				let category = maybe_category.as_mut().ok_or(Error::<T>::CategoryNotExists)?;
+				category.enabled = enabled;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::CategoryStatusChanged(id_category, enabled));
+			Ok(())
+		}
+
+		/// Creates a bookmaker vault managed by the caller.
+		#[pallet::weight(10_000)]
+		pub fn create_vault(origin: OriginFor<T>) -> DispatchResult {
+			let manager = ensure_signed(origin)?;
+			let id_vault = VaultCount::<T>::get();
+			<Vaults<T>>::insert(id_vault, Vault { manager: manager.clone(), shares: Zero::zero() });
+			VaultCount::<T>::put(id_vault.saturating_add(1));
+			Self::deposit_event(Event::VaultCreated(id_vault, manager));
+			Ok(())
+		}
+
+		/// Deposits `amount` into a vault in exchange for shares, pro rata to the vault value.
+		/// The first shares are minted at par, the funds held by a vault without shares being locked as burnt shares.
+		#[pallet::weight(10_000)]
+		pub fn deposit_to_vault(origin: OriginFor<T>, id_vault: VaultId, amount: BalanceOf<T>) -> DispatchResult {
+			let depositor = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroShares);
+			let mut vault = Self::vaults(id_vault).ok_or(Error::<T>::VaultNotExists)?;
+			ensure!(!Self::vault_settling(id_vault), Error::<T>::VaultSettling);
+			let value = Self::vault_value(id_vault);
+			let (shares, burnt) = if vault.shares.is_zero() {
+				(amount, value)
+			} else {
+				ensure!(!value.is_zero(), Error::<T>::VaultInsolvent);
+				(Self::pro_rata(amount, vault.shares, value), Zero::zero())
+			};
+			// Funds sent straight to the vault account can price a deposit below a share.
+			ensure!(!shares.is_zero(), Error::<T>::ZeroShares);
+
+			T::Currency::transfer(&depositor, &Self::vault_account(id_vault), amount, ExistenceRequirement::KeepAlive)?;
+			vault.shares = vault.shares.saturating_add(shares).saturating_add(burnt);
+			<Vaults<T>>::insert(id_vault, vault);
+			<VaultShares<T>>::mutate(id_vault, &depositor, |owned| *owned = owned.saturating_add(shares));
+			Self::deposit_event(Event::VaultDeposited(id_vault, depositor, amount, shares));
+			Ok(())
+		}
+
+		/// Burns `shares` of a vault in exchange for their value, paid from the vault funds not exposed to open bets.
+		#[pallet::weight(10_000)]
+		pub fn withdraw_from_vault(origin: OriginFor<T>, id_vault: VaultId, shares: BalanceOf<T>) -> DispatchResult {
+			let depositor = ensure_signed(origin)?;
+			let mut vault = Self::vaults(id_vault).ok_or(Error::<T>::VaultNotExists)?;
+			ensure!(!Self::vault_settling(id_vault), Error::<T>::VaultSettling);
+			let owned = Self::vault_shares(id_vault, &depositor);
+			ensure!(shares <= owned, Error::<T>::InsufficientShares);
+			let amount = Self::pro_rata(shares, Self::vault_value(id_vault), vault.shares);
+			let vault_account = Self::vault_account(id_vault);
+			ensure!(amount <= T::Currency::free_balance(&vault_account), Error::<T>::VaultInsufficientFreeBalance);
+
+			T::Currency::transfer(&vault_account, &depositor, amount, ExistenceRequirement::AllowDeath)?;
+			vault.shares = vault.shares.saturating_sub(shares);
+			<Vaults<T>>::insert(id_vault, vault);
+			if owned == shares {
+				<VaultShares<T>>::remove(id_vault, &depositor);
+			} else {
+				<VaultShares<T>>::insert(id_vault, &depositor, owned.saturating_sub(shares));
+			}
+			Self::deposit_event(Event::VaultWithdrawn(id_vault, depositor, amount, shares));
+			Ok(())
+		}
+
+		/// Sets the odds of a vault on a match, the bets on them being collateralised from the vault.
+		/// Vault manager only.
+		#[pallet::weight(10_000)]
+		pub fn set_vault_odds(
+			origin: OriginFor<T>,
+			id_vault: VaultId,
+			id_match: MatchId,
+			odds: super::Odds,
+		) -> DispatchResult {
+			let manager = ensure_signed(origin)?;
+			let vault = Self::vaults(id_vault).ok_or(Error::<T>::VaultNotExists)?;
+			ensure!(vault.manager == manager, Error::<T>::NotVaultManager);
+			Self::do_set_odds(Self::vault_account(id_vault), id_match, odds)
+		}
+
+		/// Makes a call of a bookmaker on its odds and bets on behalf of a vault, whose account owns them:
+		/// withdrawing, suspending or resuming odds, setting their bet limits, cash-out margin or bet cancellation,
+		/// and offering cash-outs. Vault manager only.
+		#[pallet::weight(call.get_dispatch_info().weight.saturating_add(T::DbWeight::get().reads(1)))]
+		pub fn vault_call(origin: OriginFor<T>, id_vault: VaultId, call: Box<Call<T>>) -> DispatchResultWithPostInfo {
+			let manager = ensure_signed(origin)?;
+			let vault = Self::vaults(id_vault).ok_or(Error::<T>::VaultNotExists)?;
+			ensure!(vault.manager == manager, Error::<T>::NotVaultManager);
+			ensure!(
+				matches!(
+					*call,
+					Call::withdraw_odds { .. } |
+						Call::suspend_odds { .. } |
+						Call::resume_odds { .. } |
+						Call::set_bet_limits { .. } |
+						Call::offer_cash_out { .. } |
+						Call::set_cash_out_margin { .. } |
+						Call::set_bet_cancellation { .. }
+				),
+				Error::<T>::NotVaultCall
+			);
+			call.dispatch_bypass_filter(frame_system::RawOrigin::Signed(Self::vault_account(id_vault)).into())
+		}
+
+		/// Places an exchange order backing or laying a prediction at `odd` or better, for a backer stake of `amount`.
+		/// The order is matched with the opposite orders of other users on the book in price-time priority,
+		/// at their odd, and any unmatched amount stays on the book until cancelled or released at the start of the match.
+		/// A back order reserves its stake, a lay order the winnable amount of the backer.
+		#[pallet::weight(
+			(10_000 as Weight)
+				.saturating_add(T::DbWeight::get().reads_writes(9, 7))
+				.saturating_add(T::DbWeight::get().reads_writes(3, 5).saturating_mul(T::MaxOrdersPerBook::get() as Weight))
+		)]
+		pub fn place_order(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+			prediction: Prediction,
+			side: OrderSide,
+			odd: Odd,
+			line: GoalLine,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(amount >= T::MinOrderAmount::get(), Error::<T>::OrderBelowMinimum);
+			ensure!(odd > Odd::one(), Error::<T>::OddOutOfBound);
+			ensure!(line % 5 == 0, Error::<T>::InvalidGoalLine);
+			let match_to_bet_on = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+			let category = Self::enabled_category(id_match.0)?;
+			ensure!(category.markets.contains(&prediction), Error::<T>::MarketNotAllowed);
+			ensure!(match_to_bet_on.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
+			ensure!(match_to_bet_on.timestamp_start > Self::now(), Error::<T>::MatchStarted);
+			let reserve = Self::order_reserve(side, odd, amount);
+			ensure!(T::Currency::can_reserve(&owner, reserve), Error::<T>::BetAccountInsufficientBalance);
+
+			// Walk the opposite book while its odds cross the order.
+			let opposite_book = Self::order_book(id_match, (prediction, side.opposite()));
+			let mut fills: Vec<(OrderId, OrderOf<T>, BalanceOf<T>)> = Vec::new();
+			let mut remaining = amount;
+			for (resting_odd, id_resting) in opposite_book.iter() {
+				let crosses = match side {
+					OrderSide::Back => *resting_odd >= odd,
+					OrderSide::Lay => *resting_odd <= odd,
+				};
+				if remaining.is_zero() || !crosses {
+					break;
+				}
+				let resting = Self::orders(id_resting).ok_or(Error::<T>::OrderNotExists)?;
+				// An order is never matched with the orders of its own owner.
+				if resting.line != line || resting.owner == owner {
+					continue;
+				}
+				let fill = remaining.min(resting.amount);
+				remaining = remaining.saturating_sub(fill);
+				fills.push((*id_resting, resting, fill));
+			}
+			let mut own_book = Self::order_book(id_match, (prediction, side));
+			ensure!(
+				remaining.is_zero() || own_book.len() < T::MaxOrdersPerBook::get() as usize,
+				Error::<T>::OrderBookFull
+			);
+			if !remaining.is_zero() {
+				Self::schedule_lapse(id_match, match_to_bet_on.timestamp_start)?;
+			}
+
+			let id_order = OrderCount::<T>::get();
+			OrderCount::<T>::put(id_order.saturating_add(1));
+			T::Currency::reserve(&owner, reserve)?;
+			let mut order = Order { owner, id_match, prediction, side, odd, line, amount };
+			for (id_resting, mut resting, fill) in fills {
+				// The bet takes the odd of the order already on the book.
+				let bet_odd = resting.odd;
+				let (back, lay) = match side {
+					OrderSide::Back => (&mut order, &mut resting),
+					OrderSide::Lay => (&mut resting, &mut order),
+				};
+				let bet = MatchedBet {
+					backer: back.owner.clone(),
+					layer: lay.owner.clone(),
+					id_match,
+					prediction,
+					line,
+					odd: bet_odd,
+					amount: fill,
+					status: BetStatus::Open,
+				};
+				back.amount = back.amount.saturating_sub(fill);
+				// The lay order keeps reserved the winnable amount of the matched stake at the bet odd,
+				// releasing what it held for the stake at its own, possibly higher, odd.
+				let lay_amount = lay.amount;
+				lay.amount = lay_amount.saturating_sub(fill);
+				let release = Self::winnable_amount(lay.odd, lay_amount)
+					.saturating_sub(Self::winnable_amount(lay.odd, lay.amount))
+					.saturating_sub(Self::winnable_amount(bet_odd, fill));
+				T::Currency::unreserve(&lay.owner, release);
+
+				let index = MatchedBetCount::<T>::get();
+				MatchedBetCount::<T>::put(index.saturating_add(1));
+				<MatchedBets<T>>::insert(index, bet);
+				if resting.amount.is_zero() {
+					<Orders<T>>::remove(id_resting);
+					<OrderBooks<T>>::mutate(id_match, (prediction, side.opposite()), |book| {
+						book.retain(|(_, id)| *id != id_resting)
+					});
+				} else {
+					<Orders<T>>::insert(id_resting, resting);
+				}
+				Self::deposit_event(Event::BetMatched(index, id_resting, id_order));
+			}
+
+			// Leave the unmatched amount on the book, after the orders at the same odd.
+			if !order.amount.is_zero() {
+				let index = match side {
+					OrderSide::Back => own_book.partition_point(|(queued, _)| *queued <= odd),
+					OrderSide::Lay => own_book.partition_point(|(queued, _)| *queued >= odd),
+				};
+				own_book.try_insert(index, (odd, id_order)).map_err(|_| Error::<T>::OrderBookFull)?;
+				<OrderBooks<T>>::insert(id_match, (prediction, side), own_book);
+				<Orders<T>>::insert(id_order, order);
+			}
+			Self::deposit_event(Event::OrderPlaced(id_order));
+			Ok(())
+		}
+
+		/// Cancels the unmatched amount of an exchange order, releasing its reserve.
+		/// Anyone can cancel the orders lapsed at the start of their match.
+		#[pallet::weight(10_000)]
+		pub fn cancel_order(origin: OriginFor<T>, id_order: OrderId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let order = Self::orders(id_order).ok_or(Error::<T>::OrderNotExists)?;
+			let lapsed = Self::matches(order.id_match).map_or(true, |selected_match| {
+				selected_match.status != MatchStatus::Open || selected_match.timestamp_start <= Self::now()
+			});
+			ensure!(order.owner == who || lapsed, Error::<T>::NotOrderOwner);
+
+			T::Currency::unreserve(&order.owner, Self::order_reserve(order.side, order.odd, order.amount));
+			<OrderBooks<T>>::mutate(order.id_match, (order.prediction, order.side), |book| {
+				book.retain(|(_, id)| *id != id_order)
+			});
+			<Orders<T>>::remove(id_order);
+			Self::deposit_event(Event::OrderCancelled(id_order));
+			Ok(())
+		}
+
+		/// Settles a matched exchange bet with the match result, paying the risk of the loser to the winner.
+		#[pallet::weight(10_000)]
+		pub fn settle_matched_bet(origin: OriginFor<T>, id_bet: MatchedBetIndex) -> DispatchResult {
+			let _who = ensure_signed(origin)?;
+			let mut bet = Self::matched_bets(id_bet).ok_or(Error::<T>::MatchedBetNotExists)?;
+			ensure!(bet.status == BetStatus::Open, Error::<T>::BetSettled);
+			let selected_match = Self::settleable_match(bet.id_match)?;
+			let bet_status: BetStatus = match selected_match.status {
+				MatchStatus::Cancelled => BetStatus::Void,
+				_ => Self::bet_outcome(&bet.prediction, bet.line, &selected_match),
+			};
+			let liability = Self::winnable_amount(bet.odd, bet.amount);
+			match bet_status {
+				BetStatus::Won => {
+					T::Currency::repatriate_reserved(&bet.layer, &bet.backer, liability, BalanceStatus::Free)?;
+					T::Currency::unreserve(&bet.backer, bet.amount);
+				},
+				BetStatus::Lost => {
+					T::Currency::repatriate_reserved(&bet.backer, &bet.layer, bet.amount, BalanceStatus::Free)?;
+					T::Currency::unreserve(&bet.layer, liability);
+				},
+				_ => {
+					T::Currency::unreserve(&bet.backer, bet.amount);
+					T::Currency::unreserve(&bet.layer, liability);
+				},
+			}
+			bet.status = bet_status;
+			<MatchedBets<T>>::insert(id_bet, bet);
+			Self::deposit_event(Event::MatchedBetSettled(id_bet));
+			Ok(())
+		}
+
+		/// Places an accumulator on the odds of `bookmaker` on several matches: the combined odd is the product of
+		/// the odds of the legs, and must be at least `min_odd`. The stake is moved to the bookmaker reserve, which
+		/// also holds the winnable amount. Settled once every match is final: it wins only if every leg wins,
+		/// void legs dropping out of the combined odd.
+		#[pallet::weight(
+			Pallet::<T>::accumulator_leg_weight()
+				.saturating_mul(T::MaxAccumulatorLegs::get() as Weight)
+				.saturating_add(Pallet::<T>::place_accumulator_weight())
+		)]
+		pub fn place_accumulator(
+			origin: OriginFor<T>,
+			bookmaker: AccountIdOf<T>,
+			selections: BoundedVec<(MatchId, Prediction), T::MaxAccumulatorLegs>,
+			amount: BalanceOf<T>,
+			min_odd: Odd,
+		) -> DispatchResult {
+			let bet_owner = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroStake);
+			let legs = Self::accumulator_legs(&bet_owner, &bookmaker, &selections, amount)?;
+			ensure!(Self::combined_odd(legs.iter()) >= min_odd, Error::<T>::OddsChanged);
+			Self::do_place_accumulator(bet_owner, bookmaker, legs, amount).map(|_| ())
+		}
+
+		/// Places a system bet on the odds of `bookmaker`: the selections are expanded to every combination with
+		/// each number of legs in `fold_sizes`, es: [2, 3] on 3 selections for a Trixie, and each combination is
+		/// placed as an accumulator of `line_amount`. The lines are settled independently through `settle_accumulator`.
+		#[pallet::weight(
+			Pallet::<T>::accumulator_leg_weight()
+				.saturating_mul(T::MaxAccumulatorLegs::get() as Weight)
+				.saturating_add(Pallet::<T>::place_accumulator_weight().saturating_mul(T::MaxSystemLines::get() as Weight))
+				.saturating_add(T::DbWeight::get().reads_writes(1, 2))
+		)]
+		pub fn place_system_bet(
+			origin: OriginFor<T>,
+			bookmaker: AccountIdOf<T>,
+			selections: BoundedVec<(MatchId, Prediction), T::MaxAccumulatorLegs>,
+			fold_sizes: BoundedVec<u32, T::MaxAccumulatorLegs>,
+			line_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let bet_owner = ensure_signed(origin)?;
+			ensure!(!line_amount.is_zero(), Error::<T>::ZeroStake);
+			let count = selections.len() as u32;
+			// Bound the expansion before computing it.
+			ensure!(!fold_sizes.is_empty(), Error::<T>::InvalidFoldSize);
+			ensure!(
+				fold_sizes.iter().all(|size| *size >= 2 && *size <= count) &&
+					fold_sizes.windows(2).all(|pair| pair[0] < pair[1]),
+				Error::<T>::InvalidFoldSize
+			);
+			let line_count = fold_sizes.iter().fold(0u32, |total, size| total.saturating_add(Self::binomial(count, *size)));
+			ensure!(line_count <= T::MaxSystemLines::get(), Error::<T>::TooManySystemLines);
+
+			let legs = Self::accumulator_legs(&bet_owner, &bookmaker, &selections, line_amount)?;
+			let mut lines: Vec<BoundedVec<AccumulatorLeg, T::MaxAccumulatorLegs>> = Vec::new();
+			for size in fold_sizes.iter() {
+				for combination in Self::combinations(legs.len(), *size as usize) {
+					let line: Vec<AccumulatorLeg> = combination.iter().map(|index| legs[*index]).collect();
+					lines.push(line.try_into().map_err(|_| Error::<T>::TooManyLegs)?);
+				}
+			}
+			// Ensure both accounts can cover every line before placing them.
+			let total_amount = line_amount.saturating_mul(BalanceOf::<T>::unique_saturated_from(line_count));
+			ensure!(T::Currency::can_reserve(&bet_owner, total_amount), Error::<T>::BetAccountInsufficientBalance);
+			let total_winnable = lines.iter().fold(Zero::zero(), |total: BalanceOf<T>, line| {
+				total.saturating_add(Self::winnable_amount(Self::combined_odd(line.iter()), line_amount))
+			});
+			ensure!(T::Currency::can_reserve(&bookmaker, total_winnable), Error::<T>::OddsAccountInsufficientBalance);
+
+			let mut accumulators: BoundedVec<AccumulatorIndex, T::MaxSystemLines> = Default::default();
+			for line in lines {
+				let id_accumulator = Self::do_place_accumulator(bet_owner.clone(), bookmaker.clone(), line, line_amount)?;
+				accumulators.try_push(id_accumulator).map_err(|_| Error::<T>::TooManySystemLines)?;
+			}
+			let id_system_bet = SystemBetCount::<T>::get();
+			SystemBetCount::<T>::put(id_system_bet.saturating_add(1));
+			<SystemBets<T>>::insert(id_system_bet, SystemBet { owner: bet_owner, line_amount, lines: accumulators });
+			Self::deposit_event(Event::SystemBetPlaced(id_system_bet));
+			Ok(())
+		}
+
+		/// Settles an accumulator once the matches of all its legs are final, unlocking all funds towards the winner.
+		#[pallet::weight(10_000)]
+		pub fn settle_accumulator(origin: OriginFor<T>, id_accumulator: AccumulatorIndex) -> DispatchResult {
+			let _who = ensure_signed(origin)?;
+			let mut accumulator = Self::accumulators(id_accumulator).ok_or(Error::<T>::AccumulatorNotExists)?;
+			ensure!(accumulator.status == BetStatus::Open, Error::<T>::BetSettled);
+			let mut won_legs: Vec<AccumulatorLeg> = Vec::new();
+			let mut lost = false;
+			for leg in accumulator.legs.iter() {
+				let selected_match = Self::settleable_match(leg.id_match)?;
+				let leg_status = match selected_match.status {
+					MatchStatus::Cancelled => BetStatus::Void,
+					_ => Self::bet_outcome(&leg.prediction, leg.line, &selected_match),
+				};
+				match leg_status {
+					BetStatus::Won => won_legs.push(*leg),
+					BetStatus::Lost => lost = true,
+					_ => {},
+				}
+			}
+			// Void legs drop out of the combined odd, the bet is void if no leg is left.
+			let bet_status = if lost {
+				BetStatus::Lost
+			} else if won_legs.is_empty() {
+				BetStatus::Void
+			} else {
+				BetStatus::Won
+			};
+			let payout = match bet_status {
+				BetStatus::Won => accumulator
+					.amount
+					.saturating_add(Self::winnable_amount(Self::combined_odd(won_legs.iter()), accumulator.amount)),
+				BetStatus::Lost => Zero::zero(),
+				_ => accumulator.amount,
+			};
+
+			// Pay off the bet from the bookmaker reserve, releasing the rest of the stake and winnable amount.
+			let held = accumulator.amount.saturating_add(Self::winnable_amount(accumulator.odd, accumulator.amount));
+			T::Currency::repatriate_reserved(&accumulator.bookmaker, &accumulator.owner, payout, BalanceStatus::Free)?;
+			T::Currency::unreserve(&accumulator.bookmaker, held.saturating_sub(payout));
+			<OpenStakes<T>>::mutate(&accumulator.bookmaker, |stakes| *stakes = stakes.saturating_sub(accumulator.amount));
+
+			accumulator.status = bet_status;
+			<Accumulators<T>>::insert(id_accumulator, accumulator);
+			Self::deposit_event(Event::AccumulatorSettled(id_accumulator));
+			Ok(())
+		}
+
+		/// Offers to settle an open bet early, paying `amount` to the bettor, or removes the offer if `None`.
+		/// Takes precedence over the cash-out margin of the odds. Bet bookmaker only.
+		#[pallet::weight(10_000)]
+		pub fn offer_cash_out(origin: OriginFor<T>, id_bet: BetIndex, amount: Option<BalanceOf<T>>) -> DispatchResult {
+			let bookmaker = ensure_signed(origin)?;
+			let bet = Self::bets(id_bet).ok_or(Error::<T>::BetNotExists)?;
+			ensure!(bet.id_odds.1 == bookmaker, Error::<T>::NotBetBookmaker);
+			ensure!(bet.status == BetStatus::Open, Error::<T>::BetSettled);
+			match amount {
+				Some(amount) => {
+					let winnable_amount = Self::winnable_amount(bet.odd, bet.amount);
+					ensure!(amount <= bet.amount.saturating_add(winnable_amount), Error::<T>::CashOutAboveWinnable);
+					<CashOutOffers<T>>::insert(id_bet, amount);
+				},
+				None => <CashOutOffers<T>>::remove(id_bet),
+			}
+			Self::deposit_event(Event::CashOutOffered(id_bet, amount));
+			Ok(())
+		}
+
+		/// Offers a cash-out on every open bet on the caller odds on a match, or stops it if `None`.
+		/// The price is the stake times the odd of the bet divided by the live odd, minus `margin`.
+		#[pallet::weight(10_000)]
+		pub fn set_cash_out_margin(origin: OriginFor<T>, id_match: MatchId, margin: Option<Permill>) -> DispatchResult {
+			let odds_owner = ensure_signed(origin)?;
+			let id_odds = (id_match, odds_owner);
+			ensure!(<Odds<T>>::contains_key(&id_odds), Error::<T>::OddsNotExist);
+			match margin {
+				Some(margin) => <CashOutMargins<T>>::insert(&id_odds, margin),
+				None => <CashOutMargins<T>>::remove(&id_odds),
+			}
+			Self::deposit_event(Event::CashOutMarginSet(id_odds, margin));
+			Ok(())
+		}
+
+		/// Settles an open bet before the match result at the cash-out price of the bookmaker, if at least `min_amount`.
+		/// The price is paid from the bookmaker reserve, which is adjusted to the remaining open bets.
+		#[pallet::weight(10_000)]
+		pub fn accept_cash_out(origin: OriginFor<T>, id_bet: BetIndex, min_amount: BalanceOf<T>) -> DispatchResult {
+			let bet_owner = ensure_signed(origin)?;
+			let bet = Self::bets(id_bet).ok_or(Error::<T>::BetNotExists)?;
+			ensure!(bet.owner == bet_owner, Error::<T>::NotBetOwner);
+			ensure!(bet.status == BetStatus::Open, Error::<T>::BetSettled);
+			let selected_match = Self::matches(bet.id_odds.0).ok_or(Error::<T>::MatchNotExists)?;
+			ensure!(
+				selected_match.status != MatchStatus::Closed && selected_match.status != MatchStatus::Cancelled,
+				Error::<T>::MatchFinalised
+			);
+			let amount = Self::cash_out_value(id_bet).ok_or(Error::<T>::NoCashOutOffer)?;
+			ensure!(amount >= min_amount, Error::<T>::CashOutChanged);
+
+			Self::close_bet_early(id_bet, bet, amount, BetStatus::CashedOut)?;
+			Self::deposit_event(Event::BetCashedOut(id_bet, amount));
+			Ok(())
+		}
+
+		/// Allows or disallows bettors to cancel the bets placed from now on the caller odds on a match.
+		#[pallet::weight(10_000)]
+		pub fn set_bet_cancellation(origin: OriginFor<T>, id_match: MatchId, allowed: bool) -> DispatchResult {
+			let odds_owner = ensure_signed(origin)?;
+			let id_odds = (id_match, odds_owner);
+			ensure!(<Odds<T>>::contains_key(&id_odds), Error::<T>::OddsNotExist);
+			if allowed {
+				<NonCancellableOdds<T>>::remove(&id_odds);
+			} else {
+				<NonCancellableOdds<T>>::insert(&id_odds, ());
+			}
+			Self::deposit_event(Event::BetCancellationSet(id_odds, allowed));
+			Ok(())
+		}
+
+		/// Cancels a bet within `CancellationPeriod` blocks from its placement and before the match starts,
+		/// refunding the stake minus the `CancellationFee`, which is paid to the bookmaker.
+		#[pallet::weight(10_000)]
+		pub fn cancel_bet(origin: OriginFor<T>, id_bet: BetIndex) -> DispatchResult {
+			let bet_owner = ensure_signed(origin)?;
+			let bet = Self::bets(id_bet).ok_or(Error::<T>::BetNotExists)?;
+			ensure!(bet.owner == bet_owner, Error::<T>::NotBetOwner);
+			ensure!(bet.status == BetStatus::Open, Error::<T>::BetSettled);
+			ensure!(bet.cancellable, Error::<T>::CancellationNotAllowed);
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() <= bet.placed_at.saturating_add(T::CancellationPeriod::get()),
+				Error::<T>::CancellationPeriodOver
+			);
+			let selected_match = Self::matches(bet.id_odds.0).ok_or(Error::<T>::MatchNotExists)?;
 			ensure!(selected_match.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
-			// Update match status and results.
-			selected_match.status = MatchStatus::Closed;
-			selected_match.home_score = home_score;
-			selected_match.away_score = away_score;
-			<Matches<T>>::insert(id_match, selected_match);
-			// todo: maybe can try also this way: <Matches<T>>::try_mutate, instead of insert.
-			
-			Self::deposit_event(Event::MatchClosed(id_match));
-			Ok(().into())
+			ensure!(selected_match.timestamp_start > Self::now(), Error::<T>::MatchStarted);
+
+			let fee = T::CancellationFee::get() * bet.amount;
+			let refund = bet.amount.saturating_sub(fee);
+			Self::close_bet_early(id_bet, bet, refund, BetStatus::Cancelled)?;
+			Self::deposit_event(Event::BetCancelled(id_bet, fee));
+			Ok(())
+		}
+
+		/// Opens a parimutuel pool on a match, creating the match if needed.
+		#[pallet::weight(10_000)]
+		pub fn open_pool(origin: OriginFor<T>, id_match: MatchId) -> DispatchResult {
+			let _who = ensure_signed(origin)?;
+			ensure!(!<Pools<T>>::contains_key(id_match), Error::<T>::PoolAlreadyExists);
+			Self::enabled_category(id_match.0)?;
+			if !<Matches<T>>::contains_key(id_match) {
+				Self::create_match(id_match);
+			}
+			<Pools<T>>::insert(id_match, Pool::default());
+			Self::deposit_event(Event::PoolOpened(id_match));
+			Ok(())
+		}
+
+		/// Adds a stake on an outcome of the 1X2 market to the parimutuel pool of an open match,
+		/// moving it to the pallet account.
+		#[pallet::weight(10_000)]
+		pub fn place_pool_bet(
+			origin: OriginFor<T>,
+			id_match: MatchId,
+			prediction: Prediction,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let bet_owner = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroStake);
+			let mut pool = Self::pools(id_match).ok_or(Error::<T>::PoolNotExists)?;
+			ensure!(Prediction::MARKETS[0].contains(&prediction), Error::<T>::PoolMarketNotAllowed);
+			let category = Self::enabled_category(id_match.0)?;
+			ensure!(category.markets.contains(&prediction), Error::<T>::MarketNotAllowed);
+			let match_to_bet_on = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+			ensure!(match_to_bet_on.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
+			ensure!(match_to_bet_on.timestamp_start > Self::now(), Error::<T>::MatchStarted);
+
+			T::Currency::transfer(&bet_owner, &Self::pool_account(), amount, ExistenceRequirement::KeepAlive)?;
+			let stakes = &mut pool.stakes[prediction as usize];
+			*stakes = stakes.saturating_add(amount);
+			<Pools<T>>::insert(id_match, pool);
+			<PoolStakes<T>>::mutate(id_match, (&bet_owner, prediction), |stake| *stake = stake.saturating_add(amount));
+			Self::deposit_event(Event::PoolBetPlaced(id_match, bet_owner, prediction, amount));
+			Ok(())
+		}
+
+		/// Pays out the winnings of the caller from the parimutuel pool of a settleable match: the pool,
+		/// minus the `PoolTake`, shared pro rata among the stakes on the winning outcome.
+		/// The share of the take of the winning stakes goes to `OnPoolTake`.
+		/// Stakes are refunded if the match is cancelled or nobody picked the winning outcome.
+		#[pallet::weight(10_000)]
+		pub fn claim_pool(origin: OriginFor<T>, id_match: MatchId) -> DispatchResult {
+			let bet_owner = ensure_signed(origin)?;
+			let pool = Self::pools(id_match).ok_or(Error::<T>::PoolNotExists)?;
+			let selected_match = Self::settleable_match(id_match)?;
+			let entries: Vec<(Prediction, BalanceOf<T>)> = Prediction::MARKETS[0]
+				.iter()
+				.map(|prediction| (*prediction, Self::pool_stakes(id_match, (&bet_owner, *prediction))))
+				.filter(|(_, stake)| !stake.is_zero())
+				.collect();
+			ensure!(!entries.is_empty(), Error::<T>::NothingToClaim);
+
+			let winner = Prediction::MARKETS[0]
+				.iter()
+				.find(|prediction| Self::bet_outcome(prediction, 0, &selected_match) == BetStatus::Won)
+				.filter(|prediction| selected_match.status == MatchStatus::Closed && !pool.stakes[**prediction as usize].is_zero());
+			let (payout, take) = entries.iter().fold(
+				(Zero::zero(), Zero::zero()),
+				|(payout, take): (BalanceOf<T>, BalanceOf<T>), (prediction, stake)| match winner {
+					Some(winner) if winner == prediction => {
+						let winners = pool.stakes[*prediction as usize];
+						let won = Self::pro_rata(*stake, Self::pool_net_total(&pool), winners);
+						let share = Self::pro_rata(*stake, Self::pool_total(&pool), winners);
+						(payout.saturating_add(won), take.saturating_add(share.saturating_sub(won)))
+					},
+					Some(_) => (payout, take),
+					None => (payout.saturating_add(*stake), take),
+				},
+			);
+
+			T::Currency::transfer(&Self::pool_account(), &bet_owner, payout, ExistenceRequirement::AllowDeath)?;
+			if !take.is_zero() {
+				let imbalance = T::Currency::withdraw(
+					&Self::pool_account(),
+					take,
+					WithdrawReasons::TRANSFER,
+					ExistenceRequirement::AllowDeath,
+				)?;
+				T::OnPoolTake::on_unbalanced(imbalance);
+			}
+			for (prediction, _) in entries {
+				<PoolStakes<T>>::remove(id_match, (&bet_owner, prediction));
+			}
+			Self::deposit_event(Event::PoolClaimed(id_match, bet_owner, payout));
+			Ok(())
+		}
+
+		/// Authorises an account to feed match data. Root only.
+		#[pallet::weight(10_000)]
+		pub fn add_oracle(
+			origin: OriginFor<T>,
+			who: AccountIdOf<T>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<Oracles<T>>::try_mutate(|oracles| -> DispatchResult {
+				ensure!(!oracles.contains(&who), Error::<T>::OracleAlreadyRegistered);
+				oracles.try_push(who.clone()).map_err(|_| Error::<T>::TooManyOracles)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::OracleAdded(who));
+			Ok(())
 		}
 
-		/// Settles a bet, unlocking all funds towards the winner.
+		/// Revokes the oracle authorisation of an account. Root only.
 		#[pallet::weight(10_000)]
-		pub fn settle_bet(
+		pub fn remove_oracle(
 			origin: OriginFor<T>,
-			id_bet: BetIndex,
+			who: AccountIdOf<T>,
 		) -> DispatchResult {
-			let _who = ensure_signed(origin)?;
-			let mut bet = Self::bets(id_bet).ok_or(Error::<T>::BetNotExists)?;
-			// Check if bet is open.
-			ensure!(bet.status == BetStatus::Open, Error::<T>::BetSettled);
-			let selected_match = Self::matches(bet.id_odds.0).ok_or(Error::<T>::MatchNotExists)?;
-			// Check if match is open.
-			ensure!(selected_match.status == MatchStatus::Closed || selected_match.status == MatchStatus::Postponed, Error::<T>::MatchOpen);
-			let bet_status: BetStatus = match bet.prediction {
-				Prediction::Homewin if selected_match.home_score > selected_match.away_score => BetStatus::Won,
-				Prediction::Awaywin if selected_match.home_score < selected_match.away_score => BetStatus::Won,
-				Prediction::Draw if selected_match.home_score == selected_match.away_score => BetStatus::Won,
-				Prediction::Over if selected_match.home_score + selected_match.away_score > 3 => BetStatus::Won,
-				Prediction::Under if selected_match.home_score + selected_match.away_score < 3 => BetStatus::Won,
-				_ => BetStatus::Lost,
-			};
-			let winnable_amount = (Percent::from_percent(bet.odd.1) * bet.amount).saturating_add(bet.amount.saturating_mul(((bet.odd.0 - 1) as u32).into()));
-			// Pay off the bet.
-			let odds_owner = &(bet.id_odds.1);
-			if bet_status == BetStatus::Won {
-				T::Currency::repatriate_reserved(odds_owner, &(bet.owner), winnable_amount, BalanceStatus::Free)?;
-				T::Currency::unreserve(&(bet.owner), bet.amount);
+			ensure_root(origin)?;
+			<Oracles<T>>::try_mutate(|oracles| -> DispatchResult {
+				let position = oracles.iter().position(|oracle| *oracle == who).ok_or(Error::<T>::OracleNotRegistered)?;
+				oracles.remove(position);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::OracleRemoved(who));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Ensure the origin is accepted by `OracleOrigin` and belongs to a registered oracle.
+	fn ensure_oracle(origin: T::Origin) -> Result<AccountIdOf<T>, DispatchError> {
+		let who = T::OracleOrigin::ensure_origin(origin)?;
+		ensure!(Self::oracles().contains(&who), Error::<T>::NotOracle);
+		Ok(who)
+	}
+
+	/// Ensure the origin is a registered oracle allowed to feed data for the category of the match.
+	fn ensure_match_oracle(origin: T::Origin, id_match: MatchId) -> Result<AccountIdOf<T>, DispatchError> {
+		let who = Self::ensure_oracle(origin)?;
+		if let Some(category) = Self::categories(id_match.0) {
+			ensure!(category.oracles.is_empty() || category.oracles.contains(&who), Error::<T>::NotCategoryOracle);
+		}
+		Ok(who)
+	}
+
+	/// Retrieve a category accepting new odds and bets.
+	fn enabled_category(id_category: MatchCategoryId) -> Result<Category<T>, DispatchError> {
+		let category = Self::categories(id_category).ok_or(Error::<T>::CategoryNotExists)?;
+		ensure!(category.enabled, Error::<T>::CategoryDisabled);
+		Ok(category)
+	}
+
+	/// Record the oracle vote for a match result, closing the match once the quorum agrees on it.
+	fn submit_match_result(
+		who: AccountIdOf<T>,
+		id_match: MatchId,
+		home_score: u32,
+		away_score: u32,
+	) -> DispatchResult {
+		let mut selected_match = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+		// Check if match is open and over.
+		ensure!(selected_match.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
+		ensure!(selected_match.finished_early || selected_match.timestamp_end <= Self::now(), Error::<T>::MatchNotFinished);
+		let score = (home_score, away_score);
+		ensure!(<ResultSubmissions<T>>::get(id_match, &who) != Some(score), Error::<T>::ResultAlreadySubmitted);
+
+		// Count the submissions agreeing with this one, the current vote included. The vote replaces
+		// the previous one of the oracle, and the votes of the oracles removed since are ignored.
+		let oracles = Self::oracles();
+		let mut agreeing: u32 = 1;
+		let mut conflicting = false;
+		for (oracle, submitted) in <ResultSubmissions<T>>::iter_prefix(id_match) {
+			if oracle == who || !oracles.contains(&oracle) {
+				continue;
+			}
+			if submitted == score {
+				agreeing = agreeing.saturating_add(1);
 			} else {
-				T::Currency::repatriate_reserved(&(bet.owner), odds_owner, bet.amount, BalanceStatus::Free)?;
-				T::Currency::unreserve(odds_owner, winnable_amount);
+				conflicting = true;
 			}
-			
-			// Change bet status and save.
-			bet.status = bet_status;
-			<Bets<T>>::insert(id_bet, bet);
-			
-			Self::deposit_event(Event::BetSettled(id_bet));
-			Ok(().into())
+		}
+		<ResultSubmissions<T>>::insert(id_match, &who, score);
+		Self::deposit_event(Event::MatchResultSubmitted(id_match, who.clone(), home_score, away_score));
+		if conflicting {
+			Self::deposit_event(Event::MatchResultConflict(id_match, who));
 		}
 
-		/// Saves the match result into storage. At the moment the results are generated randomly,
-		/// in future developments it can be called by the oracle.
-		#[pallet::weight(10_000)]
-		pub fn set_random_match_result(
-			origin: OriginFor<T>,
-			id_match: MatchId,
-		) -> DispatchResult {
-			let _who = ensure_signed(origin)?;
-			let mut selected_match = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
-			// Check if match is open.
-			ensure!(selected_match.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
+		if agreeing >= T::ResultQuorum::get() {
 			// Update match status and results.
-			// todo: randomize also MatchStatus.
 			selected_match.status = MatchStatus::Closed;
-			selected_match.home_score = Self::generate_random_score(0);
-			selected_match.away_score = Self::generate_random_score(1);
+			selected_match.home_score = home_score;
+			selected_match.away_score = away_score;
+			selected_match.dispute_end = Self::now().saturating_add(T::DisputePeriod::get());
 			<Matches<T>>::insert(id_match, selected_match);
-			// todo: maybe can try also this way: <Matches<T>>::try_mutate, instead of insert.
-			
+			<PendingSettlements<T>>::insert(id_match, ());
+			let _ = <ResultSubmissions<T>>::clear_prefix(id_match, u32::MAX, None);
+
+			Self::deposit_event(Event::MatchResultAgreed(id_match, home_score, away_score));
 			Self::deposit_event(Event::MatchClosed(id_match));
-			Ok(().into())
 		}
+		Ok(())
 	}
-}
 
-impl<T: Config> Pallet<T> {
 	/// A helper function to fetch the price and send signed transaction.
 	fn fetch_timestamp_and_send_signed(id_match : MatchId) -> Result<(), &'static str> {
 		let signer = Signer::<T, T::AuthorityId>::all_accounts();
@@ -560,7 +2421,7 @@ impl<T: Config> Pallet<T> {
 			// Received price is wrapped into a call to `submit_price` public function of this
 			// pallet. This means that the transaction, when executed, will simply call that
 			// function passing `price` as an argument.
-			Call::set_match_start { id_match, timestamp_start: timestamp }
+			Call::set_match_start { id_match, timestamp_start: timestamp, duration: None }
 		});
 
 		for (acc, res) in &results {
@@ -671,6 +2532,937 @@ impl<T: Config> Pallet<T> {
 		Ok((homescore, awayscore))
 	}
 
+	/// The bets placed on a match, in placement order.
+	pub fn match_bets(id_match: MatchId) -> Vec<BetIndex> {
+		(0..Self::match_bet_count(id_match)).filter_map(|position| <MatchBets<T>>::get(id_match, position)).collect()
+	}
+
+	/// The open bets placed by an account.
+	pub fn account_open_bets(who: &AccountIdOf<T>) -> Vec<BetIndex> {
+		<AccountBets<T>>::iter_key_prefix(who).collect()
+	}
+
+	/// The open bets accepted by a bookmaker.
+	pub fn bookmaker_open_bets(who: &AccountIdOf<T>) -> Vec<BetIndex> {
+		<BookmakerBets<T>>::iter_key_prefix(who).collect()
+	}
+
+	/// Retrieve a match whose bets can be settled: cancelled, or closed with the dispute period over
+	/// and no open dispute.
+	fn settleable_match(id_match: MatchId) -> Result<Match, DispatchError> {
+		let selected_match = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+		match selected_match.status {
+			MatchStatus::Cancelled => {},
+			MatchStatus::Closed => {
+				// Check the match result can no longer be disputed.
+				ensure!(!<Disputes<T>>::contains_key(id_match), Error::<T>::MatchDisputed);
+				ensure!(selected_match.dispute_end <= Self::now(), Error::<T>::DisputePeriodNotOver);
+			},
+			MatchStatus::Postponed => return Err(Error::<T>::MatchPostponed.into()),
+			MatchStatus::Locked | MatchStatus::Open => return Err(Error::<T>::MatchOpen.into()),
+		}
+		Ok(selected_match)
+	}
+
+	/// Settle an open bet on a settleable match, unlocking all funds towards the winner.
+	fn do_settle_bet(id_bet: BetIndex, mut bet: BetOf<T>, selected_match: &Match) -> DispatchResult {
+		let bet_status: BetStatus = match selected_match.status {
+			MatchStatus::Cancelled => BetStatus::Void,
+			_ => Self::bet_outcome(&bet.prediction, bet.line, selected_match),
+		};
+		let winnable_amount = Self::winnable_amount(bet.odd, bet.amount);
+		// Pay off the bet from the bookmaker reserve, holding its stake.
+		let odds_owner = &(bet.id_odds.1);
+		let payout = match bet_status {
+			BetStatus::Won => bet.amount.saturating_add(winnable_amount),
+			// The lost stake stays in the reserve, backing the other bets on the odds.
+			BetStatus::Lost => Zero::zero(),
+			_ => bet.amount,
+		};
+		T::Currency::repatriate_reserved(odds_owner, &(bet.owner), payout, BalanceStatus::Free)?;
+		Self::remove_from_book(&bet.id_odds, bet.prediction, bet.amount, winnable_amount, payout, bet.odds_version);
+
+		// The bet is no longer open for both accounts.
+		<AccountBets<T>>::remove(&(bet.owner), id_bet);
+		<BookmakerBets<T>>::remove(odds_owner, id_bet);
+
+		// Change bet status and save.
+		bet.status = bet_status;
+		<Bets<T>>::insert(id_bet, bet);
+
+		Self::deposit_event(Event::BetSettled(id_bet));
+		Ok(())
+	}
+
+	/// Settle the bets of a settleable match visiting at most `max_bets` entries of `MatchBets`
+	/// from the stored cursor. Returns the number of visited entries.
+	fn do_settle_match(id_match: MatchId, selected_match: &Match, max_bets: u32) -> Result<u32, DispatchError> {
+		let bet_count = Self::match_bet_count(id_match);
+		let mut cursor = Self::settlement_cursor(id_match);
+		let mut visited: u32 = 0;
+		while visited < max_bets && cursor < bet_count {
+			let entry = <MatchBets<T>>::get(id_match, cursor);
+			cursor = cursor.saturating_add(1);
+			visited = visited.saturating_add(1);
+			// Bets already settled one by one are skipped.
+			if let Some((id_bet, bet)) = entry.and_then(|id_bet| Self::bets(id_bet).map(|bet| (id_bet, bet))) {
+				if bet.status == BetStatus::Open {
+					Self::do_settle_bet(id_bet, bet, selected_match)?;
+				}
+			}
+		}
+
+		if cursor >= bet_count {
+			<SettlementCursor<T>>::remove(id_match);
+			<PendingSettlements<T>>::remove(id_match);
+			Self::deposit_event(Event::MatchFullySettled(id_match));
+		} else {
+			<SettlementCursor<T>>::insert(id_match, cursor);
+		}
+		Ok(visited)
+	}
+
+	/// The amount paid by the bookmaker to the bettor, on top of the stake, when a bet of `amount` at `odd` wins.
+	/// Shared by bet placement, reserving it from the bookmaker, and settlement.
+	pub fn winnable_amount(odd: Odd, amount: BalanceOf<T>) -> BalanceOf<T> {
+		let amount: u128 = amount.unique_saturated_into();
+		let winnable_amount = odd.saturating_sub(Odd::one()).saturating_mul_int(amount);
+		BalanceOf::<T>::unique_saturated_from(winnable_amount)
+	}
+
+	/// The odds offered by `bookmaker` on a match, expressed in the requested format.
+	/// Backs the `BetsApi` runtime API.
+	pub fn formatted_odds(id_match: MatchId, bookmaker: T::AccountId, format: OddsFormat) -> Option<FormattedOdds> {
+		let odds = Self::odds((id_match, bookmaker))?;
+		odds_format::format_odds(&odds, format)
+	}
+
+	/// Net loss of the `bookmaker` on each outcome of its odds on a match.
+	pub fn exposures(id_match: MatchId, bookmaker: T::AccountId) -> Vec<(Prediction, BalanceOf<T>)> {
+		let book = Self::book(&(id_match, bookmaker));
+		Prediction::ALL.iter().map(|prediction| (*prediction, Self::outcome_loss(&book, *prediction))).collect()
+	}
+
+	/// Worst-case net loss of the `bookmaker` on its odds on a match.
+	pub fn match_exposure(id_match: MatchId, bookmaker: &T::AccountId) -> BalanceOf<T> {
+		Self::worst_case_loss(&Self::book(&(id_match, bookmaker.clone())))
+	}
+
+	/// Amount wagered and winnable amount of the open bets on an offer.
+	fn book(id_odds: &OddsId<T>) -> Book<BalanceOf<T>> {
+		let mut book = Book::<BalanceOf<T>>::default();
+		for prediction in Prediction::ALL {
+			book[prediction as usize] = (Self::stakes(id_odds, prediction), Self::exposure(id_odds, prediction));
+		}
+		book
+	}
+
+	/// Net loss of the bookmaker if `prediction` wins: the winnable amount of the bets on it,
+	/// minus the amounts wagered on the other outcomes of its market, lost by their bettors.
+	fn outcome_loss(book: &Book<BalanceOf<T>>, prediction: Prediction) -> BalanceOf<T> {
+		let lost_stakes = prediction
+			.market()
+			.iter()
+			.filter(|other| **other != prediction)
+			.fold(Zero::zero(), |total: BalanceOf<T>, other| total.saturating_add(book[*other as usize].0));
+		book[prediction as usize].1.saturating_sub(lost_stakes)
+	}
+
+	/// Worst-case net loss of the bookmaker on an offer: the largest outcome loss of each market,
+	/// summed as the outcomes of different markets can win together.
+	fn worst_case_loss(book: &Book<BalanceOf<T>>) -> BalanceOf<T> {
+		Prediction::MARKETS.iter().fold(Zero::zero(), |total: BalanceOf<T>, market| {
+			let market_loss = market.iter().map(|prediction| Self::outcome_loss(book, *prediction)).max();
+			total.saturating_add(market_loss.unwrap_or_else(Zero::zero))
+		})
+	}
+
+	/// Remove a settled bet from the book of its offer and its `payout` from the held collateral.
+	/// What is left of the collateral is released to the bookmaker once the offer has no open bets.
+	fn remove_from_book(
+		id_odds: &OddsId<T>,
+		prediction: Prediction,
+		amount: BalanceOf<T>,
+		winnable_amount: BalanceOf<T>,
+		payout: BalanceOf<T>,
+		odds_version: OddsVersion,
+	) {
+		let version_bets = <OddsVersionBets<T>>::get(id_odds, odds_version).saturating_sub(1);
+		if version_bets == 0 {
+			<OddsVersionBets<T>>::remove(id_odds, odds_version);
+			Self::prune_odds_version(id_odds, odds_version);
+		} else {
+			<OddsVersionBets<T>>::insert(id_odds, odds_version, version_bets);
+		}
+		<OpenStakes<T>>::mutate(&id_odds.1, |stakes| *stakes = stakes.saturating_sub(amount));
+		let stakes = Self::stakes(id_odds, prediction).saturating_sub(amount);
+		if stakes.is_zero() {
+			<Stakes<T>>::remove(id_odds, prediction);
+		} else {
+			<Stakes<T>>::insert(id_odds, prediction, stakes);
+		}
+		let winnable = Self::exposure(id_odds, prediction).saturating_sub(winnable_amount);
+		if winnable.is_zero() {
+			<Exposure<T>>::remove(id_odds, prediction);
+		} else {
+			<Exposure<T>>::insert(id_odds, prediction, winnable);
+		}
+		// The line is free to move once no over/under bet is open.
+		if Prediction::MARKETS[1].iter().all(|prediction| !<Stakes<T>>::contains_key(id_odds, prediction)) {
+			<GoalLines<T>>::remove(id_odds);
+		}
+
+		let held = Self::collateral(id_odds).saturating_sub(payout);
+		if <Stakes<T>>::iter_prefix(id_odds).next().is_none() && <Exposure<T>>::iter_prefix(id_odds).next().is_none() {
+			T::Currency::unreserve(&id_odds.1, held);
+			<Collateral<T>>::remove(id_odds);
+			<BookmakerMatches<T>>::remove(&id_odds.1, id_odds.0);
+		} else {
+			<Collateral<T>>::insert(id_odds, held);
+		}
+	}
+
+	/// Implied probability sum of each market of some odds, counting the predictions in `markets`.
+	pub fn margin(odds: &Odds, markets: &[Prediction]) -> OddsMargin {
+		let market_margin = |market: &[Prediction]| {
+			let allowed: Vec<&Prediction> = market.iter().filter(|prediction| markets.contains(prediction)).collect();
+			if allowed.len() < 2 {
+				return None
+			}
+			Some(allowed.iter().fold(FixedU128::zero(), |total, prediction| {
+				total.saturating_add(odds.odd(prediction).reciprocal().unwrap_or_default())
+			}))
+		};
+		OddsMargin { result: market_margin(Prediction::MARKETS[0]), total_goals: market_margin(Prediction::MARKETS[1]) }
+	}
+
+	/// The margin of the odds offered by `bookmaker` on a match. Backs the `BetsApi` runtime API.
+	pub fn odds_margin(id_match: MatchId, bookmaker: T::AccountId) -> Option<OddsMargin> {
+		let odds = Self::odds((id_match, bookmaker))?;
+		let markets = Self::categories(id_match.0).map(|category| category.markets.into_inner())?;
+		Some(Self::margin(&odds, &markets))
+	}
+
+	/// The legs of an accumulator of `amount` on the live odds of `bookmaker`, checking each can take the bet.
+	fn accumulator_legs(
+		bet_owner: &AccountIdOf<T>,
+		bookmaker: &AccountIdOf<T>,
+		selections: &[(MatchId, Prediction)],
+		amount: BalanceOf<T>,
+	) -> Result<BoundedVec<AccumulatorLeg, T::MaxAccumulatorLegs>, DispatchError> {
+		ensure!(selections.len() >= 2, Error::<T>::AccumulatorTooShort);
+		ensure!(bet_owner != bookmaker, Error::<T>::SameMatchOwner);
+		// The accumulator liability is held apart from the offer books, the vault shares cannot be priced on it.
+		ensure!(!Self::is_vault_account(bookmaker), Error::<T>::VaultAccumulator);
+		let now = Self::now();
+		let mut legs: BoundedVec<AccumulatorLeg, T::MaxAccumulatorLegs> = Default::default();
+		for (id_match, prediction) in selections.iter() {
+			ensure!(legs.iter().all(|leg| leg.id_match != *id_match), Error::<T>::DuplicateLeg);
+			let id_odds = (*id_match, bookmaker.clone());
+			let odds = Self::odds(&id_odds).ok_or(Error::<T>::OddsNotExist)?;
+			let match_to_bet_on = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+			ensure!(!Self::suspension(&id_odds).is_suspended(prediction), Error::<T>::OddsSuspended);
+			let category = Self::enabled_category(id_match.0)?;
+			ensure!(category.markets.contains(prediction), Error::<T>::MarketNotAllowed);
+			ensure!(match_to_bet_on.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
+			ensure!(match_to_bet_on.timestamp_start > now, Error::<T>::MatchStarted);
+			ensure!(odds.valid_until.map_or(true, |valid_until| valid_until > now), Error::<T>::OddsExpired);
+			let limits = Self::bet_limits(&id_odds);
+			ensure!(limits.max_stake.map_or(true, |max| amount <= max), Error::<T>::StakeAboveLimit);
+			// The accumulator liability is held apart from the offer book, the liability limits cannot account for it.
+			ensure!(
+				limits.max_outcome_liability.is_none() && limits.max_match_liability.is_none(),
+				Error::<T>::LiabilityLimitedOdds
+			);
+			let leg = AccumulatorLeg { id_match: *id_match, prediction: *prediction, odd: odds.odd(prediction), line: odds.line };
+			legs.try_push(leg).map_err(|_| Error::<T>::TooManyLegs)?;
+		}
+		Ok(legs)
+	}
+
+	/// Number of combinations of `size` elements out of `count`, saturating.
+	fn binomial(count: u32, size: u32) -> u32 {
+		if size > count {
+			return 0
+		}
+		let size = size.min(count - size) as u64;
+		let binomial = (0..size).fold(1u64, |binomial, i| {
+			binomial.saturating_mul((count as u64).saturating_sub(i)) / i.saturating_add(1)
+		});
+		binomial.min(u32::MAX as u64) as u32
+	}
+
+	/// Every combination of `size` indices out of `0..count`, in lexicographic order.
+	fn combinations(count: usize, size: usize) -> Vec<Vec<usize>> {
+		let mut combinations = Vec::new();
+		if size == 0 || size > count {
+			return combinations
+		}
+		let mut indices: Vec<usize> = (0..size).collect();
+		loop {
+			combinations.push(indices.clone());
+			// Find the rightmost index that can still move forward.
+			let mut position = size;
+			while position > 0 && indices[position - 1] == count - size + position - 1 {
+				position -= 1;
+			}
+			if position == 0 {
+				return combinations
+			}
+			indices[position - 1] += 1;
+			for next in position..size {
+				indices[next] = indices[next - 1] + 1;
+			}
+		}
+	}
+
+	/// Product of the odds of some accumulator legs.
+	fn combined_odd<'a>(legs: impl Iterator<Item = &'a AccumulatorLeg>) -> Odd {
+		legs.fold(Odd::one(), |odd, leg| odd.saturating_mul(leg.odd))
+	}
+
+	/// Place an accumulator of `bet_owner`, moving the stake to the bookmaker reserve along with the winnable amount.
+	fn do_place_accumulator(
+		bet_owner: AccountIdOf<T>,
+		bookmaker: AccountIdOf<T>,
+		legs: BoundedVec<AccumulatorLeg, T::MaxAccumulatorLegs>,
+		amount: BalanceOf<T>,
+	) -> Result<AccumulatorIndex, DispatchError> {
+		let odd = Self::combined_odd(legs.iter());
+		let winnable_amount = Self::winnable_amount(odd, amount);
+		ensure!(T::Currency::can_reserve(&bet_owner, amount), Error::<T>::BetAccountInsufficientBalance);
+		ensure!(T::Currency::can_reserve(&bookmaker, winnable_amount), Error::<T>::OddsAccountInsufficientBalance);
+		T::Currency::reserve(&bet_owner, amount)?;
+		T::Currency::repatriate_reserved(&bet_owner, &bookmaker, amount, BalanceStatus::Reserved)?;
+		T::Currency::reserve(&bookmaker, winnable_amount)?;
+		<OpenStakes<T>>::mutate(&bookmaker, |stakes| *stakes = stakes.saturating_add(amount));
+
+		let id_accumulator = AccumulatorCount::<T>::get();
+		AccumulatorCount::<T>::put(id_accumulator.saturating_add(1));
+		let accumulator = Accumulator { owner: bet_owner, bookmaker, legs, odd, amount, status: BetStatus::Open };
+		<Accumulators<T>>::insert(id_accumulator, accumulator);
+		Self::deposit_event(Event::AccumulatorPlaced(id_accumulator));
+		Ok(id_accumulator)
+	}
+
+	/// Close an open bet before the match result paying `payout` to the bettor from the bookmaker reserve,
+	/// then adjust the reserve to the stakes plus worst-case net loss of the other open bets on the offer.
+	fn close_bet_early(id_bet: BetIndex, mut bet: BetOf<T>, payout: BalanceOf<T>, status: BetStatus) -> DispatchResult {
+		// Ensure the bookmaker can keep covering the remaining open bets.
+		let id_odds = bet.id_odds.clone();
+		let winnable_amount = Self::winnable_amount(bet.odd, bet.amount);
+		let mut book = Self::book(&id_odds);
+		let (stakes, winnable) = book[bet.prediction as usize];
+		book[bet.prediction as usize] = (stakes.saturating_sub(bet.amount), winnable.saturating_sub(winnable_amount));
+		let collateral = book.iter().fold(Self::worst_case_loss(&book), |total, (stakes, _)| total.saturating_add(*stakes));
+		let held = Self::collateral(&id_odds).saturating_sub(payout);
+		ensure!(
+			collateral <= held || T::Currency::can_reserve(&id_odds.1, collateral.saturating_sub(held)),
+			Error::<T>::OddsAccountInsufficientBalance
+		);
+
+		T::Currency::repatriate_reserved(&id_odds.1, &bet.owner, payout, BalanceStatus::Free)?;
+		Self::remove_from_book(&id_odds, bet.prediction, bet.amount, winnable_amount, payout, bet.odds_version);
+		if <Collateral<T>>::contains_key(&id_odds) {
+			if collateral > held {
+				T::Currency::reserve(&id_odds.1, collateral.saturating_sub(held))?;
+			} else {
+				T::Currency::unreserve(&id_odds.1, held.saturating_sub(collateral));
+			}
+			<Collateral<T>>::insert(&id_odds, collateral);
+		}
+
+		// The bet is no longer open for both accounts.
+		<AccountBets<T>>::remove(&bet.owner, id_bet);
+		<BookmakerBets<T>>::remove(&id_odds.1, id_bet);
+		<CashOutOffers<T>>::remove(id_bet);
+		bet.status = status;
+		<Bets<T>>::insert(id_bet, bet);
+		Ok(())
+	}
+
+	/// The cash-out price of an open bet: the offer of the bookmaker on the bet, otherwise the price given by
+	/// the cash-out margin and live odds of the offer until the match starts, freezing the odds,
+	/// never above the stake plus the winnable amount.
+	/// Backs the `BetsApi` runtime API.
+	pub fn cash_out_value(id_bet: BetIndex) -> Option<BalanceOf<T>> {
+		let bet = Self::bets(id_bet).filter(|bet| bet.status == BetStatus::Open)?;
+		if let Some(amount) = Self::cash_out_offers(id_bet) {
+			return Some(amount)
+		}
+		let margin = Self::cash_out_margin(&bet.id_odds)?;
+		let selected_match = Self::matches(bet.id_odds.0)?;
+		if selected_match.status != MatchStatus::Open || selected_match.timestamp_start <= Self::now() {
+			return None
+		}
+		let live_odd = Self::odds(&bet.id_odds)?.odd(&bet.prediction);
+		let stake: u128 = bet.amount.unique_saturated_into();
+		let value = Odd::checked_from_rational(bet.odd.into_inner(), live_odd.into_inner())?.saturating_mul_int(stake);
+		let value = BalanceOf::<T>::unique_saturated_from(value);
+		let value = value.saturating_sub(margin * value);
+		Some(value.min(bet.amount.saturating_add(Self::winnable_amount(bet.odd, bet.amount))))
+	}
+
+	/// The pallet account, holding the stakes of the parimutuel pools.
+	pub fn pool_account() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
+	/// The stakes of a pool on every outcome.
+	fn pool_total(pool: &Pool<BalanceOf<T>>) -> BalanceOf<T> {
+		pool.stakes.iter().fold(Zero::zero(), |total: BalanceOf<T>, stakes| total.saturating_add(*stakes))
+	}
+
+	/// The stakes of a pool shared by the winners, net of the `PoolTake`.
+	fn pool_net_total(pool: &Pool<BalanceOf<T>>) -> BalanceOf<T> {
+		let total = Self::pool_total(pool);
+		total.saturating_sub(T::PoolTake::get() * total)
+	}
+
+	/// Live implied odd of each outcome of the parimutuel pool of a match, `None` if it has no stake.
+	/// Backs the `BetsApi` runtime API.
+	pub fn pool_odds(id_match: MatchId) -> Vec<(Prediction, Option<Odd>)> {
+		let pool = Self::pools(id_match).unwrap_or_default();
+		let net_total: u128 = Self::pool_net_total(&pool).unique_saturated_into();
+		Prediction::MARKETS[0]
+			.iter()
+			.map(|prediction| {
+				let stakes: u128 = pool.stakes[*prediction as usize].unique_saturated_into();
+				(*prediction, Odd::checked_from_rational(net_total, stakes))
+			})
+			.collect()
+	}
+
+	/// Amount reserved for the unmatched backer stake of an exchange order.
+	fn order_reserve(side: OrderSide, odd: Odd, amount: BalanceOf<T>) -> BalanceOf<T> {
+		match side {
+			OrderSide::Back => amount,
+			OrderSide::Lay => Self::winnable_amount(odd, amount),
+		}
+	}
+
+	/// The account holding the funds of a vault and owning its odds.
+	pub fn vault_account(id_vault: VaultId) -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating((VAULT_ACCOUNT_TAG, id_vault))
+	}
+
+	/// Whether an account is the account of a vault.
+	fn is_vault_account(who: &T::AccountId) -> bool {
+		<PalletId as AccountIdConversion<T::AccountId>>::try_from_sub_account::<([u8; 5], VaultId)>(who)
+			.map_or(false, |(pallet_id, (tag, _))| pallet_id == T::PalletId::get() && tag == *VAULT_ACCOUNT_TAG)
+	}
+
+	/// Whether a vault has open bets on started matches: their outcome is being decided,
+	/// but not yet reflected in `vault_value`.
+	pub fn vault_settling(id_vault: VaultId) -> bool {
+		let now = Self::now();
+		<BookmakerMatches<T>>::iter_key_prefix(Self::vault_account(id_vault)).any(|id_match| {
+			Self::matches(id_match).map_or(false, |selected_match| match selected_match.status {
+				MatchStatus::Closed | MatchStatus::Postponed => true,
+				MatchStatus::Open => selected_match.timestamp_start <= now,
+				MatchStatus::Locked | MatchStatus::Cancelled => false,
+			})
+		})
+	}
+
+	/// Value of the shares of a vault: its free funds and the collateral held for the bets it accepted,
+	/// except their stakes. Profits and losses of the settled bets flow into it, the odds deposits do not.
+	pub fn vault_value(id_vault: VaultId) -> BalanceOf<T> {
+		let vault_account = Self::vault_account(id_vault);
+		let collateral = <BookmakerMatches<T>>::iter_key_prefix(&vault_account)
+			.fold(BalanceOf::<T>::zero(), |held, id_match| {
+				held.saturating_add(Self::collateral((id_match, vault_account.clone())))
+			});
+		T::Currency::free_balance(&vault_account)
+			.saturating_add(collateral)
+			.saturating_sub(Self::open_stakes(&vault_account))
+	}
+
+	/// `amount * numerator / denominator`, rounded down.
+	fn pro_rata(amount: BalanceOf<T>, numerator: BalanceOf<T>, denominator: BalanceOf<T>) -> BalanceOf<T> {
+		let (amount, numerator, denominator): (u128, u128, u128) =
+			(amount.unique_saturated_into(), numerator.unique_saturated_into(), denominator.unique_saturated_into());
+		BalanceOf::<T>::unique_saturated_from(amount.saturating_mul(numerator).checked_div(denominator).unwrap_or(0))
+	}
+
+	/// The offer book with a new bet of `amount` at `odd`, the collateral it requires and the amount already held for it.
+	/// Fails if the bet breaks the limits of the bookmaker or the bookmaker cannot reserve the collateral.
+	fn book_with_bet(
+		id_odds: &OddsId<T>,
+		prediction: Prediction,
+		odd: Odd,
+		amount: BalanceOf<T>,
+	) -> Result<(Book<BalanceOf<T>>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
+		// Ensure that the bet is within the limits set by the bookmaker.
+		let limits = Self::bet_limits(id_odds);
+		ensure!(limits.max_stake.map_or(true, |max| amount <= max), Error::<T>::StakeAboveLimit);
+		// Add the bet to the offer book to evaluate the bookmaker net exposure.
+		let mut book = Self::book(id_odds);
+		let (stakes, winnable) = book[prediction as usize];
+		book[prediction as usize] =
+			(stakes.saturating_add(amount), winnable.saturating_add(Self::winnable_amount(odd, amount)));
+		ensure!(
+			limits.max_outcome_liability.map_or(true, |max| Self::outcome_loss(&book, prediction) <= max),
+			Error::<T>::OutcomeLiabilityExceeded
+		);
+		let worst_case_loss = Self::worst_case_loss(&book);
+		ensure!(
+			limits.max_match_liability.map_or(true, |max| worst_case_loss <= max),
+			Error::<T>::MatchLiabilityExceeded
+		);
+		// The bookmaker reserve holds the stakes plus the worst-case net loss.
+		let collateral = book.iter().fold(worst_case_loss, |total, (stakes, _)| total.saturating_add(*stakes));
+		let held = Self::collateral(id_odds).saturating_add(amount);
+		// Ensure that bookie account have suffient free balance.
+		ensure!(
+			collateral <= held || T::Currency::can_reserve(&id_odds.1, collateral.saturating_sub(held)),
+			Error::<T>::OddsAccountInsufficientBalance
+		);
+		Ok((book, collateral, held))
+	}
+
+	/// The largest part of `amount`, at `odd`, the bookmaker of an offer can accept.
+	fn max_fill(id_odds: &OddsId<T>, prediction: Prediction, odd: Odd, amount: BalanceOf<T>) -> BalanceOf<T> {
+		// The bookmaker liability grows with the stake: search the largest accepted one.
+		let (mut low, mut high): (u128, u128) = (0, amount.unique_saturated_into());
+		while low < high {
+			let mid = low.saturating_add(high.saturating_sub(low).saturating_add(1) / 2);
+			let stake = BalanceOf::<T>::unique_saturated_from(mid);
+			if Self::book_with_bet(id_odds, prediction, odd, stake).is_ok() {
+				low = mid;
+			} else {
+				high = mid.saturating_sub(1);
+			}
+		}
+		BalanceOf::<T>::unique_saturated_from(low)
+	}
+
+	/// Place a bet of `bet_owner` on the odds of `odds_owner`, moving the stake to the bookmaker reserve.
+	fn do_place_bet(
+		bet_owner: AccountIdOf<T>,
+		id_match: MatchId,
+		odds_owner: AccountIdOf<T>,
+		prediction: Prediction,
+		amount: BalanceOf<T>,
+		expected: OddsGuard,
+	) -> DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::ZeroStake);
+		let bet_index = BetCount::<T>::get();
+		// Retrieve the match struct and match_owner
+		let odds = Self::odds((id_match, odds_owner.clone())).ok_or(Error::<T>::OddsNotExist)?;
+		let match_to_bet_on = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+		// Ensure bet owner and match owner are not the same account.
+		ensure!(bet_owner != odds_owner.clone(), Error::<T>::SameMatchOwner);
+		// Ensure the bookmaker has not suspended the prediction.
+		ensure!(
+			!Self::suspension((id_match, odds_owner.clone())).is_suspended(&prediction),
+			Error::<T>::OddsSuspended
+		);
+		// Ensure the market is open to bets in the match category.
+		let category = Self::enabled_category(id_match.0)?;
+		ensure!(category.markets.contains(&prediction), Error::<T>::MarketNotAllowed);
+		// Ensure match is open.
+		ensure!(match_to_bet_on.status == MatchStatus::Open, Error::<T>::MatchNotOpen);
+		// Ensure that the bet takes place before the match starts.
+		let timestamp: <T as pallet_timestamp::Config>::Moment = <pallet_timestamp::Pallet<T>>::get();
+		let mut timestamp_u64 = 0u64; // initialize
+		if let Ok(_timestamp) = Self::convert_moment_to_u64_in_milliseconds(timestamp) {
+			timestamp_u64 = _timestamp;
+		}
+		ensure!(match_to_bet_on.timestamp_start > timestamp_u64, Error::<T>::MatchStarted);
+		// Ensure that the odds are still valid.
+		ensure!(odds.valid_until.map_or(true, |valid_until| valid_until > timestamp_u64), Error::<T>::OddsExpired);
+		// Ensure that bettor account have suffient free balance.
+		ensure!(T::Currency::can_reserve(&bet_owner, amount), Error::<T>::BetAccountInsufficientBalance);
+
+		let odd: Odd = odds.odd(&prediction);
+		// Ensure the live odds are the ones accepted by the bettor.
+		let odds_version = Self::odds_version((id_match, odds_owner.clone()));
+		let accepted = match expected {
+			OddsGuard::Version(version) => version == odds_version,
+			OddsGuard::MinOdd(min_odd, line) => {
+				odd >= min_odd && (!Prediction::MARKETS[1].contains(&prediction) || odds.line == line)
+			},
+		};
+		ensure!(accepted, Error::<T>::OddsChanged);
+		let id_odds = (id_match, odds_owner.clone());
+		let (book, collateral, held) = Self::book_with_bet(&id_odds, prediction, odd, amount)?;
+		// Move the stake to the bookmaker reserve, then adjust the reserve to the new collateral.
+		T::Currency::reserve(&bet_owner, amount)?;
+		T::Currency::repatriate_reserved(&bet_owner, &odds_owner, amount, BalanceStatus::Reserved)?;
+		if collateral > held {
+			T::Currency::reserve(&odds_owner, collateral.saturating_sub(held))?;
+		} else {
+			T::Currency::unreserve(&odds_owner, held.saturating_sub(collateral));
+		}
+		<Collateral<T>>::insert(&id_odds, collateral);
+		<BookmakerMatches<T>>::insert(&odds_owner, id_match, ());
+		<OpenStakes<T>>::mutate(&odds_owner, |stakes| *stakes = stakes.saturating_add(amount));
+		<Stakes<T>>::insert(&id_odds, prediction, book[prediction as usize].0);
+		<Exposure<T>>::insert(&id_odds, prediction, book[prediction as usize].1);
+		<OddsVersionBets<T>>::mutate(&id_odds, odds_version, |version_bets| *version_bets = version_bets.saturating_add(1));
+		if Prediction::MARKETS[1].contains(&prediction) {
+			<GoalLines<T>>::insert(&id_odds, odds.line);
+		}
+
+		// Index the bet by match, bettor and bookmaker.
+		let position = Self::match_bet_count(id_match);
+		<MatchBets<T>>::insert(id_match, position, bet_index);
+		<MatchBetCount<T>>::insert(id_match, position.saturating_add(1));
+		<AccountBets<T>>::insert(&bet_owner, bet_index, ());
+		<BookmakerBets<T>>::insert(&odds_owner, bet_index, ());
+
+		let bet = Bet {
+			owner: bet_owner,
+			id_odds: (id_match, odds_owner),
+			prediction,
+			odd,
+			line: odds.line,
+			amount,
+			status: BetStatus::Open,
+			odds_version,
+			placed_at: <frame_system::Pallet<T>>::block_number(),
+			cancellable: !<NonCancellableOdds<T>>::contains_key(&id_odds),
+		};
+
+		// Insert bet into storage.
+		<Bets<T>>::insert(bet_index, bet);
+		// Not protected against overflow.
+		BetCount::<T>::put(bet_index + 1);
+
+		// Emit Event
+		Self::deposit_event(Event::BetPlaced(bet_index));
+		Ok(())
+	}
+
+	/// Store new odds of `odds_owner` on a match, creating the match if needed.
+	fn do_set_odds(odds_owner: T::AccountId, id_match: MatchId, odds: Odds) -> DispatchResult {
+		// Check every Odd pays more than the stake.
+		ensure!(
+			[odds.homewin, odds.awaywin, odds.draw, odds.under, odds.over].iter().all(|odd| *odd > Odd::one()),
+			Error::<T>::OddOutOfBound
+		);
+		// Check the over/under line is a whole or half-goal line.
+		ensure!(odds.line % 5 == 0, Error::<T>::InvalidGoalLine);
+		// Check the odds were not withdrawn.
+		let id_odds = (id_match, odds_owner.clone());
+		ensure!(!<WithdrawnOdds<T>>::contains_key(&id_odds), Error::<T>::OddsWithdrawn);
+		// Check the validity of the odds, if limited, ends in the future.
+		ensure!(odds.valid_until.map_or(true, |valid_until| valid_until > Self::now()), Error::<T>::OddsExpired);
+		// Check the line does not move under open over/under bets, netted on a single line,
+		// even once their odds expired.
+		ensure!(Self::goal_line(&id_odds).map_or(true, |line| line == odds.line), Error::<T>::GoalLineLocked);
+		// While the match has room, a new bookmaker takes a place among its bookmakers, reserving its deposit.
+		// Once they are full, its odds are still offered to `place_bet`, but not routed to by `place_bet_best`
+		// until it updates them after a place frees up, as for the bookmakers left out by the version 5 migration.
+		let bookmakers = Self::match_bookmakers(id_match);
+		let deposit = T::OddsDeposit::get();
+		let listed = bookmakers.contains(&odds_owner);
+		let room = bookmakers.len() < T::MaxBookmakersPerMatch::get() as usize;
+		if !listed && room && !<pallet::Odds<T>>::contains_key(&id_odds) {
+			ensure!(T::Currency::can_reserve(&odds_owner, deposit), Error::<T>::OddsAccountInsufficientBalance);
+		}
+		let indexed = !listed && room && T::Currency::can_reserve(&odds_owner, deposit);
+		// Check the match category is known and enabled.
+		let category = Self::enabled_category(id_match.0)?;
+		// Check the margin of each market is within the allowed overround.
+		let margin = Self::margin(&odds, &category.markets);
+		let min_margin = FixedU128::one().saturating_add(T::MinOverround::get().into());
+		let max_margin = FixedU128::one().saturating_add(T::MaxOverround::get().into());
+		for market_margin in [margin.result, margin.total_goals].iter().flatten() {
+			ensure!(*market_margin >= min_margin, Error::<T>::OverroundTooLow);
+			ensure!(*market_margin <= max_margin, Error::<T>::OverroundTooHigh);
+		}
+
+		// Track the validity of the odds, replacing the one of the previous version.
+		let previous_until = Self::odds(&id_odds).and_then(|current_odds| current_odds.valid_until);
+		Self::schedule_expiry(&id_odds, previous_until, odds.valid_until)?;
+
+		// If the match is not in storage, add it.
+		if !<Matches<T>>::contains_key(id_match) {
+			Self::create_match(id_match);
+		} else {
+			// Check match has not already started.
+			let match_to_book_on = Self::matches(id_match).ok_or(Error::<T>::MatchNotExists)?;
+			let mut timestamp_u64 = 0u64; // initialize
+			if let Ok(_timestamp) = Self::convert_moment_to_u64_in_milliseconds(<pallet_timestamp::Pallet<T>>::get()) {
+				timestamp_u64 = _timestamp;
+			}
+			ensure!(match_to_book_on.timestamp_start > timestamp_u64, Error::<T>::MatchStarted);
+		}
+
+		// Store the odds as a new version, keeping the previous ones in the history.
+		let version = Self::odds_version(&id_odds).saturating_add(1);
+		let created = !<pallet::Odds<T>>::contains_key(&id_odds);
+		if indexed {
+			let mut bookmakers = bookmakers;
+			bookmakers.try_push(odds_owner.clone()).map_err(|_| Error::<T>::TooManyBookmakers)?;
+			T::Currency::reserve(&odds_owner, deposit)?;
+			<MatchBookmakers<T>>::insert(id_match, bookmakers);
+			<OddsDeposits<T>>::insert(&id_odds, deposit);
+		}
+		<pallet::Odds<T>>::insert(&id_odds, odds);
+		<OddsVersions<T>>::insert(&id_odds, version);
+		<OddsHistory<T>>::insert(&id_odds, version, odds);
+		Self::prune_odds_version(&id_odds, version.saturating_sub(1));
+
+		if created {
+			Self::deposit_event(Event::OddsCreated(id_odds));
+		} else {
+			Self::deposit_event(Event::OddsUpdated(id_odds, version));
+		}
+		Ok(())
+	}
+
+	/// Remove a version of the odds of an offer from `OddsHistory`, unless it is live or accepted by open bets.
+	fn prune_odds_version(id_odds: &OddsId<T>, version: OddsVersion) {
+		let live = <Odds<T>>::contains_key(id_odds) && Self::odds_version(id_odds) == version;
+		if !live && !<OddsVersionBets<T>>::contains_key(id_odds, version) {
+			<OddsHistory<T>>::remove(id_odds, version);
+		}
+	}
+
+	/// Remove the live odds of a withdrawn or expired offer together with the settings the bookmaker attached
+	/// to them, its place among the bookmakers of the match and its live version, unless accepted by open bets.
+	fn remove_offer(id_odds: &OddsId<T>) {
+		<Odds<T>>::remove(id_odds);
+		<OddsLimits<T>>::remove(id_odds);
+		<Suspensions<T>>::remove(id_odds);
+		<CashOutMargins<T>>::remove(id_odds);
+		<NonCancellableOdds<T>>::remove(id_odds);
+		Self::remove_match_bookmaker(id_odds);
+		Self::prune_odds_version(id_odds, Self::odds_version(id_odds));
+	}
+
+	/// Remove the bookmaker of an offer from the bookmakers of its match, releasing its deposit.
+	fn remove_match_bookmaker(id_odds: &OddsId<T>) {
+		<MatchBookmakers<T>>::mutate(id_odds.0, |bookmakers| bookmakers.retain(|bookmaker| *bookmaker != id_odds.1));
+		T::Currency::unreserve(&id_odds.1, <OddsDeposits<T>>::take(id_odds));
+	}
+
+	/// Store a new Locked match, waiting for its start time.
+	fn create_match(id_match: MatchId) {
+		let match_to_create = Match {
+			status: MatchStatus::Locked,
+			home_score: 0, 
+			away_score: 0,
+			timestamp_start: 0,
+			timestamp_end: 0,
+			finished_early: false,
+			dispute_end: 0,
+		};
+		// Store the match with id_match as key.
+		<Matches<T>>::insert(id_match, match_to_create);
+		Self::deposit_event(Event::MatchCreated(id_match));
+	}
+
+	/// Bucket of `ExpiringOdds` of the odds valid until `valid_until`.
+	fn expiry_bucket(valid_until: u64) -> u64 {
+		valid_until.checked_div(T::ExpiryBucket::get()).unwrap_or(valid_until)
+	}
+
+	/// Move an offer in `ExpiringOdds` from the bucket of its `previous` validity to the one of `valid_until`.
+	fn schedule_expiry(id_odds: &OddsId<T>, previous: Option<u64>, valid_until: Option<u64>) -> DispatchResult {
+		let previous = previous.map(Self::expiry_bucket);
+		let next = valid_until.map(Self::expiry_bucket);
+		if previous == next {
+			return Ok(())
+		}
+		if let Some(bucket) = next {
+			<ExpiringOdds<T>>::try_mutate(bucket, |queued| queued.try_push(id_odds.clone()))
+				.map_err(|_| Error::<T>::TooManyExpiringOdds)?;
+			// Start pruning from the current bucket, the earliest one a validity can end in.
+			if Self::expiry_cursor().is_none() {
+				<ExpiryCursor<T>>::put(Self::expiry_bucket(Self::now()));
+			}
+		}
+		if let Some(bucket) = previous {
+			let mut queued = Self::expiring_odds(bucket);
+			queued.retain(|queued| queued != id_odds);
+			if queued.is_empty() {
+				<ExpiringOdds<T>>::remove(bucket);
+			} else {
+				<ExpiringOdds<T>>::insert(bucket, queued);
+			}
+		}
+		Ok(())
+	}
+
+	/// Remove from `Odds` the offers of the buckets of `ExpiringOdds` entirely in the past,
+	/// as far as `remaining_weight` allows. Returns the weight used.
+	fn prune_expired_odds(remaining_weight: Weight) -> Weight {
+		// Reading and writing the cursor, then each bucket and the offers it holds, removing them
+		// with their settings, their bookmaker from the bookmakers of the match and their live version from the history.
+		let cursor_weight = T::DbWeight::get().reads_writes(1, 1);
+		let bucket_weight = T::DbWeight::get().reads_writes(1, 1);
+		let odds_weight = T::DbWeight::get().reads_writes(5, 8);
+		if remaining_weight < cursor_weight {
+			return 0
+		}
+		let mut cursor = match Self::expiry_cursor() {
+			Some(cursor) => cursor,
+			None => return T::DbWeight::get().reads(1),
+		};
+
+		let lapsed = Self::expiry_bucket(Self::now());
+		let mut used_weight = cursor_weight;
+		while cursor < lapsed {
+			let queued = Self::expiring_odds(cursor);
+			let weight = bucket_weight.saturating_add(odds_weight.saturating_mul(queued.len() as Weight));
+			if used_weight.saturating_add(weight) > remaining_weight {
+				break
+			}
+			<ExpiringOdds<T>>::remove(cursor);
+			for id_odds in queued {
+				Self::remove_offer(&id_odds);
+				Self::deposit_event(Event::OddsExpired(id_odds));
+			}
+			used_weight = used_weight.saturating_add(weight);
+			cursor = cursor.saturating_add(1);
+		}
+		<ExpiryCursor<T>>::put(cursor);
+		used_weight
+	}
+
+	/// Queue in `LapsingBooks` a match leaving orders on its books, to release them once it starts.
+	fn schedule_lapse(id_match: MatchId, timestamp_start: u64) -> DispatchResult {
+		<LapsingBooks<T>>::try_mutate(Self::expiry_bucket(timestamp_start), |queued| -> DispatchResult {
+			if !queued.contains(&id_match) {
+				queued.try_push(id_match).map_err(|_| Error::<T>::TooManyLapsingBooks)?;
+			}
+			Ok(())
+		})?;
+		// Start releasing from the current bucket, the earliest one a match can start in.
+		if Self::lapse_cursor().is_none() {
+			<LapseCursor<T>>::put(Self::expiry_bucket(Self::now()));
+		}
+		Ok(())
+	}
+
+	/// Release the orders left on the books of the matches of the buckets of `LapsingBooks` entirely in the past,
+	/// as far as `remaining_weight` allows. Returns the weight used.
+	fn release_lapsed_orders(remaining_weight: Weight) -> Weight {
+		// Reading and writing the cursor, then each bucket and the matches it holds, draining every book of a match
+		// and unreserving and removing each of its orders.
+		let cursor_weight = T::DbWeight::get().reads_writes(1, 1);
+		let bucket_weight = T::DbWeight::get().reads_writes(1, 1);
+		let books = Prediction::MARKETS.iter().map(|market| market.len()).sum::<usize>().saturating_mul(2);
+		let match_weight = T::DbWeight::get().reads_writes(2, 1).saturating_add(
+			T::DbWeight::get()
+				.reads_writes(1, 1)
+				.saturating_add(T::DbWeight::get().reads_writes(2, 2).saturating_mul(T::MaxOrdersPerBook::get() as Weight))
+				.saturating_mul(books as Weight),
+		);
+		if remaining_weight < cursor_weight {
+			return 0
+		}
+		let mut cursor = match Self::lapse_cursor() {
+			Some(cursor) => cursor,
+			None => return T::DbWeight::get().reads(1),
+		};
+
+		let lapsed = Self::expiry_bucket(Self::now());
+		let mut used_weight = cursor_weight;
+		while cursor < lapsed {
+			if used_weight.saturating_add(bucket_weight) > remaining_weight {
+				break
+			}
+			used_weight = used_weight.saturating_add(bucket_weight);
+			let mut queued = Self::lapsing_books(cursor);
+			while let Some(id_match) = queued.last().copied() {
+				if used_weight.saturating_add(match_weight) > remaining_weight {
+					break
+				}
+				used_weight = used_weight.saturating_add(match_weight);
+				queued.pop();
+				Self::release_lapsed_books(id_match);
+			}
+			// A bucket left with matches is resumed by the next block.
+			if !queued.is_empty() {
+				<LapsingBooks<T>>::insert(cursor, queued);
+				break
+			}
+			<LapsingBooks<T>>::remove(cursor);
+			cursor = cursor.saturating_add(1);
+		}
+		<LapseCursor<T>>::put(cursor);
+		used_weight
+	}
+
+	/// Release the orders left on the books of a match once it started, or was postponed or cancelled.
+	/// The books of a match rescheduled to a later start are queued again for it.
+	fn release_lapsed_books(id_match: MatchId) {
+		if let Some(selected_match) = Self::matches(id_match) {
+			if selected_match.status == MatchStatus::Open && selected_match.timestamp_start > Self::now() {
+				if let Err(e) = Self::schedule_lapse(id_match, selected_match.timestamp_start) {
+					log::warn!("Unable to queue the orders of match {:?} for its new start: {:?}", id_match, e);
+				}
+				return
+			}
+		}
+		for (_, book) in <OrderBooks<T>>::drain_prefix(id_match) {
+			for (_, id_order) in book {
+				if let Some(order) = <Orders<T>>::take(id_order) {
+					T::Currency::unreserve(&order.owner, Self::order_reserve(order.side, order.odd, order.amount));
+					Self::deposit_event(Event::OrderCancelled(id_order));
+				}
+			}
+		}
+	}
+
+	/// Upper bound of the weight needed to place a single bet.
+	fn place_bet_weight() -> Weight {
+		(10_000 as Weight).saturating_add(T::DbWeight::get().reads_writes(20, 14))
+	}
+
+	/// Upper bound of the weight needed to rank the odds of a bookmaker and to search, in `max_fill`,
+	/// the largest stake it accepts: every step of the search reads the same keys again.
+	fn fill_search_weight() -> Weight {
+		// The odds and suspension of the offer, then its limits, book, collateral and bookmaker account.
+		(10_000 as Weight)
+			.saturating_mul(u128::BITS as Weight)
+			.saturating_add(T::DbWeight::get().reads(15))
+	}
+
+	/// Upper bound of the weight needed to check a leg of an accumulator, reading the odds, match, suspension,
+	/// category and limits of its offer.
+	fn accumulator_leg_weight() -> Weight {
+		(10_000 as Weight).saturating_add(T::DbWeight::get().reads(5))
+	}
+
+	/// Upper bound of the weight needed to store an accumulator, combining the odds of up to `MaxAccumulatorLegs` legs
+	/// and moving the stake and winnable amount to the bookmaker reserve.
+	fn place_accumulator_weight() -> Weight {
+		(10_000 as Weight)
+			.saturating_mul(T::MaxAccumulatorLegs::get() as Weight)
+			.saturating_add(T::DbWeight::get().reads_writes(4, 6))
+	}
+
+	/// Upper bound of the weight needed to settle a single bet, reading its entry of `MatchBets`.
+	fn settle_bet_weight() -> Weight {
+		(10_000 as Weight).saturating_add(T::DbWeight::get().reads_writes(9, 8))
+	}
+
+	/// Establish the outcome of a prediction given the final result of the match.
+	/// Over/under predictions are void (push) when the total goals land exactly on the line.
+	fn bet_outcome(prediction: &Prediction, line: GoalLine, selected_match: &Match) -> BetStatus {
+		let total_goals = selected_match.home_score.saturating_add(selected_match.away_score).saturating_mul(10);
+		match prediction {
+			Prediction::Homewin if selected_match.home_score > selected_match.away_score => BetStatus::Won,
+			Prediction::Awaywin if selected_match.home_score < selected_match.away_score => BetStatus::Won,
+			Prediction::Draw if selected_match.home_score == selected_match.away_score => BetStatus::Won,
+			Prediction::Over if total_goals > line => BetStatus::Won,
+			Prediction::Under if total_goals < line => BetStatus::Won,
+			Prediction::Over | Prediction::Under if total_goals == line => BetStatus::Void,
+			_ => BetStatus::Lost,
+		}
+	}
+
+	/// Current timestamp in milliseconds, 0 if the conversion fails.
+	fn now() -> u64 {
+		Self::convert_moment_to_u64_in_milliseconds(<pallet_timestamp::Pallet<T>>::get()).unwrap_or(0)
+	}
+
 	fn convert_moment_to_u64_in_milliseconds(date: T::Moment) -> Result<u64, DispatchError> {
         let date_as_u64_millis;
         if let Some(_date_as_u64) = TryInto::<u64>::try_into(date).ok() {
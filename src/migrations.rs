@@ -0,0 +1,421 @@
+//! Storage migrations for the bets pallet.
+
+use super::*;
+use frame_support::storage::unhashed;
+
+/// Rewrite the values stored at the hashed `keys` of a storage map from the `Old` to the `New` layout.
+/// Unlike `translate`, the new layout does not have to be the current one, so each migration writes the layout
+/// of its own version. Values failing to decode are removed. Returns the number of values visited.
+fn translate_layout<K, Old: Decode, New: Encode>(
+	keys: impl Iterator<Item = K>,
+	hashed_key: impl Fn(&K) -> Vec<u8>,
+	mut upgrade: impl FnMut(&K, Old) -> New,
+) -> u64 {
+	let mut translated: u64 = 0;
+	for key in keys {
+		let hashed_key = hashed_key(&key);
+		translated = translated.saturating_add(1);
+		match unhashed::get::<Old>(&hashed_key) {
+			Some(old) => unhashed::put(&hashed_key, &upgrade(&key, old)),
+			None => {
+				log::error!("Unable to decode a value in the layout before the migration, removed");
+				unhashed::kill(&hashed_key);
+			},
+		}
+	}
+	translated
+}
+
+/// Version 1: odds stored as fixed point numbers instead of (integer part, percent) tuples,
+/// odds and bets storing the over/under line instead of a hard-coded 3-goal line, matches storing
+/// the end of their dispute window.
+pub mod v1 {
+	use super::*;
+
+	/// Odd touple composed by integer e fractional part through Percent, used before version 1.
+	pub type OldOdd = (u32, u8);
+
+	/// The over/under line hard-coded before version 1: 3 goals.
+	pub const OLD_GOAL_LINE: GoalLine = 30;
+
+	/// Odds layout used before version 1, without an over/under line.
+	#[derive(Encode, Decode)]
+	pub struct OldOdds {
+		pub homewin: OldOdd,
+		pub awaywin: OldOdd,
+		pub draw: OldOdd,
+		pub under: OldOdd,
+		pub over: OldOdd,
+	}
+
+	/// Bet layout used before version 1, without an over/under line.
+	#[derive(Encode, Decode)]
+	pub struct OldBet<AccountId, Balance, OddsId> {
+		pub owner: AccountId,
+		pub id_odds: OddsId,
+		pub prediction: Prediction,
+		pub odd: OldOdd,
+		pub amount: Balance,
+		pub status: BetStatus,
+	}
+
+	/// Match layout used before version 1, without expected end and dispute window.
+	#[derive(Encode, Decode)]
+	pub struct OldMatch {
+		pub status: MatchStatus,
+		pub home_score: u32,
+		pub away_score: u32,
+		pub timestamp_start: u64,
+	}
+
+	/// Convert a match to the current layout: expected to last `DefaultMatchDuration`, with no dispute window.
+	pub fn upgrade_match<T: Config>(old: OldMatch) -> Match {
+		Match {
+			status: old.status,
+			home_score: old.home_score,
+			away_score: old.away_score,
+			timestamp_start: old.timestamp_start,
+			timestamp_end: old.timestamp_start.saturating_add(T::DefaultMatchDuration::get()),
+			finished_early: false,
+			dispute_end: 0,
+		}
+	}
+
+	/// Convert an old odd, es: (2, 50) is 2.50.
+	pub fn convert_odd(old: OldOdd) -> Odd {
+		Odd::saturating_from_rational((old.0 as u128).saturating_mul(100).saturating_add(old.1 as u128), 100u128)
+	}
+
+	/// Convert the stored `Odds` and the `Bet::odd` values to the fixed point odd type,
+	/// setting the over/under line to the one hard-coded before, and the stored `Matches` to the current layout.
+	/// Odds and bets are written in their version 1 layout, `v4::OldOdds` and `v3::OldBet`.
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+		if on_chain_version >= 1 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut translated: u64 = 0;
+		crate::pallet::Matches::<T>::translate::<OldMatch, _>(|_, old| {
+			translated = translated.saturating_add(1);
+			Some(upgrade_match::<T>(old))
+		});
+		translated = translated.saturating_add(translate_layout(
+			crate::pallet::Odds::<T>::iter_keys(),
+			|id_odds| crate::pallet::Odds::<T>::hashed_key_for(id_odds),
+			|_, old: OldOdds| v4::OldOdds {
+				homewin: convert_odd(old.homewin),
+				awaywin: convert_odd(old.awaywin),
+				draw: convert_odd(old.draw),
+				under: convert_odd(old.under),
+				over: convert_odd(old.over),
+				line: OLD_GOAL_LINE,
+			},
+		));
+		translated = translated.saturating_add(translate_layout(
+			crate::pallet::Bets::<T>::iter_keys(),
+			|id_bet| crate::pallet::Bets::<T>::hashed_key_for(id_bet),
+			|_, old: OldBet<AccountIdOf<T>, BalanceOf<T>, OddsId<T>>| v3::OldBet {
+				owner: old.owner,
+				id_odds: old.id_odds,
+				prediction: old.prediction,
+				odd: convert_odd(old.odd),
+				line: OLD_GOAL_LINE,
+				amount: old.amount,
+				status: old.status,
+			},
+		));
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!("Migrated {} matches, odds and bets", translated);
+		T::DbWeight::get().reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+	}
+}
+
+/// Version 2: bet stakes held in the bookmaker reserve, which is sized on the net exposure of each offer.
+pub mod v2 {
+	use super::*;
+
+	/// Move the stakes of the open bets to the bookmaker reserve and rebuild the book of each offer.
+	/// The collateral starts as the amount already reserved, stakes plus every winnable amount,
+	/// and is released when the offer is settled. An open bet whose stake cannot be moved is voided,
+	/// releasing both reserves, and left out of the book. Bets keep their version 1 layout, `v3::OldBet`.
+	/// The remaining open bets are indexed by match, bettor and bookmaker, so that bulk settlement and the
+	/// account queries find them, and their matches already closed or cancelled are queued for settlement.
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+		if on_chain_version >= 2 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let _ = crate::pallet::Exposure::<T>::clear(u32::MAX, None);
+		let mut migrated: u64 = 0;
+		let mut voided: u64 = 0;
+		let translated = translate_layout(
+			crate::pallet::Bets::<T>::iter_keys(),
+			|id_bet| crate::pallet::Bets::<T>::hashed_key_for(id_bet),
+			|id_bet, bet: v3::OldBet<AccountIdOf<T>, BalanceOf<T>, OddsId<T>>| {
+				if bet.status != BetStatus::Open {
+					return bet
+				}
+				let winnable_amount = Pallet::<T>::winnable_amount(bet.odd, bet.amount);
+				let odds_owner = &(bet.id_odds.1);
+				let stake_moved = T::Currency::reserved_balance(&bet.owner) >= bet.amount &&
+					match T::Currency::repatriate_reserved(&bet.owner, odds_owner, bet.amount, BalanceStatus::Reserved) {
+						Ok(_) => true,
+						Err(error) => {
+							log::error!("Failed to move the stake of an open bet: {:?}", error);
+							false
+						},
+					};
+				if !stake_moved {
+					T::Currency::unreserve(&bet.owner, bet.amount);
+					T::Currency::unreserve(odds_owner, winnable_amount);
+					voided = voided.saturating_add(1);
+					return v3::OldBet { status: BetStatus::Void, ..bet }
+				}
+				crate::pallet::Stakes::<T>::mutate(&bet.id_odds, bet.prediction, |stakes| *stakes = stakes.saturating_add(bet.amount));
+				if Prediction::MARKETS[1].contains(&bet.prediction) {
+					crate::pallet::GoalLines::<T>::insert(&bet.id_odds, bet.line);
+				}
+				crate::pallet::Exposure::<T>::mutate(&bet.id_odds, bet.prediction, |winnable| {
+					*winnable = winnable.saturating_add(winnable_amount)
+				});
+				crate::pallet::BookmakerMatches::<T>::insert(odds_owner, bet.id_odds.0, ());
+				crate::pallet::Collateral::<T>::mutate(&bet.id_odds, |held| {
+					*held = held.saturating_add(bet.amount).saturating_add(winnable_amount)
+				});
+				let id_match = bet.id_odds.0;
+				let position = crate::pallet::MatchBetCount::<T>::get(id_match);
+				crate::pallet::MatchBets::<T>::insert(id_match, position, *id_bet);
+				crate::pallet::MatchBetCount::<T>::insert(id_match, position.saturating_add(1));
+				crate::pallet::AccountBets::<T>::insert(&bet.owner, *id_bet, ());
+				crate::pallet::BookmakerBets::<T>::insert(odds_owner, *id_bet, ());
+				let settleable = crate::pallet::Matches::<T>::get(id_match)
+					.map_or(false, |m| m.status == MatchStatus::Closed || m.status == MatchStatus::Cancelled);
+				if settleable {
+					crate::pallet::PendingSettlements::<T>::insert(id_match, ());
+				}
+				migrated = migrated.saturating_add(1);
+				bet
+			},
+		);
+
+		StorageVersion::new(2).put::<Pallet<T>>();
+		log::info!("Moved the stakes of {} open bets to the bookmaker reserve", migrated);
+		if voided > 0 {
+			log::error!("Voided {} open bets whose stake could not be moved", voided);
+		}
+		T::DbWeight::get().reads_writes(
+			translated.saturating_add(migrated.saturating_mul(5)).saturating_add(voided.saturating_mul(3)).saturating_add(1),
+			translated.saturating_add(migrated.saturating_mul(10)).saturating_add(voided.saturating_mul(2)).saturating_add(1),
+		)
+	}
+}
+
+/// Version 3: bets reference the version of the odds they accepted.
+pub mod v3 {
+	use super::*;
+
+	/// Bet layout from version 1 to version 2, with the fixed point odd and the over/under line.
+	#[derive(Encode, Decode)]
+	pub struct OldBet<AccountId, Balance, OddsId> {
+		pub owner: AccountId,
+		pub id_odds: OddsId,
+		pub prediction: Prediction,
+		pub odd: Odd,
+		pub line: GoalLine,
+		pub amount: Balance,
+		pub status: BetStatus,
+	}
+
+	/// Convert a bet to the version 3 layout, `v6::OldBet`: bets placed before versioning accepted version 0.
+	pub fn upgrade_bet<AccountId, Balance, OddsId>(
+		old: OldBet<AccountId, Balance, OddsId>,
+	) -> v6::OldBet<AccountId, Balance, OddsId> {
+		v6::OldBet {
+			owner: old.owner,
+			id_odds: old.id_odds,
+			prediction: old.prediction,
+			odd: old.odd,
+			line: old.line,
+			amount: old.amount,
+			status: old.status,
+			odds_version: 0,
+		}
+	}
+
+	/// Add the odds version to the stored bets, written in their version 3 layout. The versions of the live odds
+	/// start from 0, the next `set_odds` of an offer storing version 1: the live odds are stored as version 0
+	/// in `OddsHistory`, kept there while the open bets placed before the upgrade accept it.
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+		if on_chain_version >= 3 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut open: u64 = 0;
+		let translated = translate_layout(
+			crate::pallet::Bets::<T>::iter_keys(),
+			|id_bet| crate::pallet::Bets::<T>::hashed_key_for(id_bet),
+			|_, old: OldBet<AccountIdOf<T>, BalanceOf<T>, OddsId<T>>| {
+				if old.status == BetStatus::Open {
+					crate::pallet::OddsVersionBets::<T>::mutate(&old.id_odds, 0, |version_bets| {
+						*version_bets = version_bets.saturating_add(1)
+					});
+					open = open.saturating_add(1);
+				}
+				upgrade_bet(old)
+			},
+		);
+		// The live odds are still in the version 1 layout, copied as they are.
+		let mut seeded: u64 = 0;
+		for id_odds in crate::pallet::Odds::<T>::iter_keys() {
+			if let Some(odds) = unhashed::get_raw(&crate::pallet::Odds::<T>::hashed_key_for(&id_odds)) {
+				unhashed::put_raw(&crate::pallet::OddsHistory::<T>::hashed_key_for(&id_odds, 0), &odds);
+				seeded = seeded.saturating_add(1);
+			}
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+		log::info!("Added the odds version to {} bets, stored {} live odds as version 0", translated, seeded);
+		T::DbWeight::get().reads_writes(
+			translated.saturating_add(open).saturating_add(seeded).saturating_add(1),
+			translated.saturating_add(open).saturating_add(seeded).saturating_add(1),
+		)
+	}
+}
+
+/// Version 4: odds offers with an optional `valid_until` timestamp.
+pub mod v4 {
+	use super::*;
+
+	/// Odds layout used before version 4.
+	#[derive(Encode, Decode)]
+	pub struct OldOdds {
+		pub homewin: Odd,
+		pub awaywin: Odd,
+		pub draw: Odd,
+		pub under: Odd,
+		pub over: Odd,
+		pub line: GoalLine,
+	}
+
+	/// Convert odds to the current layout, valid until the match start.
+	pub fn upgrade_odds(old: OldOdds) -> crate::Odds {
+		crate::Odds {
+			homewin: old.homewin,
+			awaywin: old.awaywin,
+			draw: old.draw,
+			under: old.under,
+			over: old.over,
+			line: old.line,
+			valid_until: None,
+		}
+	}
+
+	/// Add the `valid_until` timestamp to the live odds and to their history.
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+		if on_chain_version >= 4 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut translated: u64 = 0;
+		crate::pallet::Odds::<T>::translate::<OldOdds, _>(|_, old| {
+			translated = translated.saturating_add(1);
+			Some(upgrade_odds(old))
+		});
+		crate::pallet::OddsHistory::<T>::translate::<OldOdds, _>(|_, _, old| {
+			translated = translated.saturating_add(1);
+			Some(upgrade_odds(old))
+		});
+
+		StorageVersion::new(4).put::<Pallet<T>>();
+		log::info!("Added the validity to {} odds", translated);
+		T::DbWeight::get().reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+	}
+}
+
+/// Version 5: index of the bookmakers offering odds on each match.
+pub mod v5 {
+	use super::*;
+
+	/// Index the bookmakers of the live odds by match.
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+		if on_chain_version >= 5 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut indexed: u64 = 0;
+		for ((id_match, bookmaker), _) in crate::pallet::Odds::<T>::iter() {
+			let pushed = crate::pallet::MatchBookmakers::<T>::mutate(id_match, |bookmakers| {
+				bookmakers.try_push(bookmaker).is_ok()
+			});
+			if pushed {
+				indexed = indexed.saturating_add(1);
+			} else {
+				log::warn!("Too many bookmakers on match {:?}, not indexed", id_match);
+			}
+		}
+
+		StorageVersion::new(5).put::<Pallet<T>>();
+		log::info!("Indexed {} bookmakers by match", indexed);
+		T::DbWeight::get().reads_writes(indexed.saturating_add(1), indexed.saturating_add(1))
+	}
+}
+
+/// Version 6: bets record the block they were placed in, starting their cancellation period,
+/// and whether they can be cancelled.
+pub mod v6 {
+	use super::*;
+
+	/// Bet layout used before version 6.
+	#[derive(Encode, Decode)]
+	pub struct OldBet<AccountId, Balance, OddsId> {
+		pub owner: AccountId,
+		pub id_odds: OddsId,
+		pub prediction: Prediction,
+		pub odd: Odd,
+		pub line: GoalLine,
+		pub amount: Balance,
+		pub status: BetStatus,
+		pub odds_version: OddsVersion,
+	}
+
+	/// Convert a bet to the current layout, bets placed before version 6 are past their cancellation period
+	/// and cannot be cancelled.
+	pub fn upgrade_bet<T: Config>(old: OldBet<AccountIdOf<T>, BalanceOf<T>, OddsId<T>>) -> BetOf<T> {
+		Bet {
+			owner: old.owner,
+			id_odds: old.id_odds,
+			prediction: old.prediction,
+			odd: old.odd,
+			line: old.line,
+			amount: old.amount,
+			status: old.status,
+			odds_version: old.odds_version,
+			placed_at: Zero::zero(),
+			cancellable: false,
+		}
+	}
+
+	/// Add the placement block and the cancellation flag to the stored bets.
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+		if on_chain_version >= 6 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut translated: u64 = 0;
+		crate::pallet::Bets::<T>::translate::<OldBet<AccountIdOf<T>, BalanceOf<T>, OddsId<T>>, _>(|_, old| {
+			translated = translated.saturating_add(1);
+			Some(upgrade_bet::<T>(old))
+		});
+
+		StorageVersion::new(6).put::<Pallet<T>>();
+		log::info!("Added the placement block to {} bets", translated);
+		T::DbWeight::get().reads_writes(translated.saturating_add(1), translated.saturating_add(1))
+	}
+}
@@ -201,6 +201,11 @@ impl pallet_aura::Config for Test {
 
 parameter_types! {
 	pub const BetsPalletId: PalletId = PalletId(*b"py/bbets");
+	pub const MinOverround: Permill = Permill::zero();
+	pub const MaxOverround: Permill = Permill::one();
+	pub const PoolTake: Permill = Permill::from_percent(10);
+	pub const CancellationFee: Permill = Permill::from_percent(10);
+	pub static OddsDeposit: u64 = 0;
 }
 
 impl Config for Test {
@@ -209,12 +214,67 @@ impl Config for Test {
 	type Event = Event;
 	type Randomness = TestRandomness<Self>;
 	type AuthorityId = pallet_bets::crypto::TestAuthId;
+	type OracleOrigin = frame_system::EnsureSigned<Self::AccountId>;
+	type MaxOracles = ConstU32<8>;
+	type ResultQuorum = ConstU32<2>;
+	type DisputePeriod = ConstU64<1000>;
+	type DisputeBond = ConstU64<10>;
+	type DisputeResolutionOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	type Slash = ();
+	type DefaultMatchDuration = ConstU64<1000>;
+	type MaxCategoryNameLength = ConstU32<32>;
+	type ExpiryBucket = ConstU64<1000>;
+	type MaxExpiringOdds = ConstU32<2>;
+	type MinOverround = MinOverround;
+	type MaxOverround = MaxOverround;
+	type MaxOrdersPerBook = ConstU32<2>;
+	type MinOrderAmount = ConstU64<5>;
+	type MaxLapsingBooks = ConstU32<2>;
+	type MaxBookmakersPerMatch = ConstU32<3>;
+	type OddsDeposit = OddsDeposit;
+	type PoolTake = PoolTake;
+	type OnPoolTake = ();
+	type MaxAccumulatorLegs = ConstU32<4>;
+	type MaxSystemLines = ConstU32<3>;
+	type CancellationPeriod = ConstU64<10>;
+	type CancellationFee = CancellationFee;
 }
 
+/// Accounts registered as oracles in the test externalities.
+pub const ORACLE: u8 = 5;
+pub const SECOND_ORACLE: u8 = 6;
+
 pub fn acc_pub(acc_num: u8) -> sp_core::sr25519::Public {
 	sp_core::sr25519::Public::from_raw([acc_num; 32])
 }
 
+/// Odds of 2.0 on every market, over/under line of 3 goals.
+pub fn even_odds() -> Odds {
+	let two = Odd::saturating_from_integer(2);
+	Odds { homewin: two, awaywin: two, draw: two, under: two, over: two, line: 30, valid_until: None }
+}
+
+/// Accept any version of the odds on the 3-goal line when placing a bet.
+pub fn any_odds() -> OddsGuard {
+	OddsGuard::MinOdd(Odd::one(), 30)
+}
+
+/// Category of the matches used in tests, allowing every market.
+pub const FOOTBALL: MatchCategoryId = 1;
+
+pub fn football_category() -> Category<Test> {
+	Category {
+		name: b"Football League".to_vec().try_into().unwrap(),
+		sport: Sport::Football,
+		markets: vec![Prediction::Homewin, Prediction::Awaywin, Prediction::Draw, Prediction::Under, Prediction::Over]
+			.try_into()
+			.unwrap(),
+		duration: 0,
+		oracles: Default::default(),
+		enabled: true,
+	}
+}
+
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test> {
@@ -223,7 +283,14 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	.assimilate_storage(&mut t)
 	.unwrap();
 
-	t.into()
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Bets::add_oracle(Origin::root(), acc_pub(ORACLE)).unwrap();
+		Bets::add_oracle(Origin::root(), acc_pub(SECOND_ORACLE)).unwrap();
+		Bets::set_category(Origin::root(), FOOTBALL, football_category()).unwrap();
+	});
+	ext
 }
 
 pub fn new_test_ext_ocw() -> sp_io::TestExternalities {
@@ -259,14 +326,9 @@ pub fn new_test_ext_ocw() -> sp_io::TestExternalities {
 	ext.execute_with(|| {
 		//Timestamp::set_timestamp(Timestamp::now());
 		let id_match = (1,23);
-		let odds = Odds {
-			homewin: (2,00),
-			awaywin: (2,00),
-			draw: (2,00),
-			under: (2,00),
-			over: (2,00),
-		};
+		let odds = even_odds();
 		assert_eq!(Balances::total_issuance(), 500);
+		Bets::set_category(Origin::root(), FOOTBALL, football_category()).unwrap();
 		Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds).unwrap();
 		Bets::fetch_timestamp_and_send_signed(id_match).unwrap();
 		// let match_created = Bets::matches(id_match).unwrap();
@@ -275,7 +337,7 @@ pub fn new_test_ext_ocw() -> sp_io::TestExternalities {
 		assert!(pool_state.read().transactions.is_empty());
 		let tx = Extrinsic::decode(&mut &*tx).unwrap();
 		assert_eq!(tx.signature.unwrap().0, 0);
-		assert_eq!(tx.call, Call::Bets(crate::Call::set_match_start { id_match: (id_match), timestamp_start: (1667758138) }));
+		assert_eq!(tx.call, Call::Bets(crate::Call::set_match_start { id_match: (id_match), timestamp_start: (1667758138), duration: None }));
 		// let match_created = Bets::matches(id_match).unwrap();
 		// assert_eq!(match_created.timestamp_start, 1667758138);
 	});
@@ -0,0 +1,146 @@
+//! Conversion of odds between the decimal format used on-chain and the fractional and American
+//! formats, so that every client formats odds the same way.
+//!
+//! Rounding rules:
+//! * **Fractional:** the profit over the stake (decimal odd - 1) is converted to the closest
+//!   fraction whose denominator is at most `MAX_DENOMINATOR`, es: 2.5 is 3/2, 1.3333 is 1/3.
+//! * **American:** odds of at least 2.0 are converted to the profit on a stake of 100 (+150 for 2.5),
+//!   lower odds to the negative stake needed to win 100 (-200 for 1.5), both rounded to the nearest
+//!   integer, halves rounded up.
+
+use crate::{GoalLine, Odd, Odds};
+use codec::{Decode, Encode};
+use frame_support::{
+	sp_runtime::{
+		traits::{One, Saturating},
+		FixedPointNumber,
+	},
+	RuntimeDebug,
+};
+use scale_info::TypeInfo;
+use sp_std::convert::TryFrom;
+
+/// Largest denominator of the fractional odds.
+pub const MAX_DENOMINATOR: u128 = 1_000;
+
+/// The formats odds can be expressed in.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, TypeInfo, PartialEq, Eq)]
+pub enum OddsFormat {
+	Decimal,
+	Fractional,
+	American,
+}
+
+/// An odd expressed in one of the supported formats.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, TypeInfo, PartialEq, Eq)]
+pub enum FormattedOdd {
+	/// Decimal odd, stake included, es: 2.5.
+	Decimal(Odd),
+	/// Fractional odd as numerator and denominator of the profit over the stake, es: 3/2.
+	Fractional(u64, u64),
+	/// American odd, es: +150 or -200.
+	American(i64),
+}
+
+/// The odds of an offer expressed in one of the supported formats.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, TypeInfo, PartialEq, Eq)]
+pub struct FormattedOdds {
+	pub homewin: FormattedOdd,
+	pub awaywin: FormattedOdd,
+	pub draw: FormattedOdd,
+	pub under: FormattedOdd,
+	pub over: FormattedOdd,
+	pub line: GoalLine,
+}
+
+/// Express a decimal odd in the requested format, `None` if the odd is not greater than 1
+/// or the result does not fit the format.
+pub fn format_odd(odd: Odd, format: OddsFormat) -> Option<FormattedOdd> {
+	let profit = odd.into_inner().checked_sub(Odd::DIV).filter(|profit| *profit > 0)?;
+	match format {
+		OddsFormat::Decimal => Some(FormattedOdd::Decimal(odd)),
+		OddsFormat::Fractional => {
+			let (numerator, denominator) = limit_denominator(profit, Odd::DIV);
+			Some(FormattedOdd::Fractional(u64::try_from(numerator).ok()?, u64::try_from(denominator).ok()?))
+		},
+		OddsFormat::American if profit >= Odd::DIV => {
+			let american = profit.saturating_mul(100).saturating_add(Odd::DIV / 2) / Odd::DIV;
+			Some(FormattedOdd::American(i64::try_from(american).ok()?))
+		},
+		OddsFormat::American => {
+			let american = Odd::DIV.saturating_mul(100).saturating_add(profit / 2) / profit;
+			Some(FormattedOdd::American(-i64::try_from(american).ok()?))
+		},
+	}
+}
+
+/// Convert an odd expressed in any format to the decimal odd, `None` if it is not valid.
+pub fn parse_odd(odd: FormattedOdd) -> Option<Odd> {
+	let profit = match odd {
+		FormattedOdd::Decimal(odd) => return Some(odd).filter(|odd| *odd > Odd::one()),
+		FormattedOdd::Fractional(numerator, denominator) =>
+			Odd::checked_from_rational(numerator, denominator)?,
+		FormattedOdd::American(american) if american >= 100 =>
+			Odd::checked_from_rational(american as u128, 100u128)?,
+		FormattedOdd::American(american) if american <= -100 =>
+			Odd::checked_from_rational(100u128, american.unsigned_abs() as u128)?,
+		FormattedOdd::American(_) => return None,
+	};
+	Some(Odd::one().saturating_add(profit)).filter(|odd| *odd > Odd::one())
+}
+
+/// Express all the odds of an offer in the requested format.
+pub fn format_odds(odds: &Odds, format: OddsFormat) -> Option<FormattedOdds> {
+	Some(FormattedOdds {
+		homewin: format_odd(odds.homewin, format)?,
+		awaywin: format_odd(odds.awaywin, format)?,
+		draw: format_odd(odds.draw, format)?,
+		under: format_odd(odds.under, format)?,
+		over: format_odd(odds.over, format)?,
+		line: odds.line,
+	})
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+	while b != 0 {
+		let remainder = a % b;
+		a = b;
+		b = remainder;
+	}
+	a
+}
+
+/// The fraction closest to `numerator / denominator` with denominator at most `MAX_DENOMINATOR`,
+/// found through its continued fraction expansion.
+fn limit_denominator(numerator: u128, denominator: u128) -> (u128, u128) {
+	let divisor = gcd(numerator, denominator);
+	let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+	if denominator <= MAX_DENOMINATOR {
+		return (numerator, denominator)
+	}
+
+	// Last two convergents of the expansion.
+	let (mut p0, mut q0, mut p1, mut q1) = (0u128, 1u128, 1u128, 0u128);
+	let (mut n, mut d) = (numerator, denominator);
+	while d != 0 {
+		let a = n / d;
+		let q2 = q0.saturating_add(a.saturating_mul(q1));
+		if q2 > MAX_DENOMINATOR {
+			break
+		}
+		let p2 = p0.saturating_add(a.saturating_mul(p1));
+		(p0, q0, p1, q1) = (p1, q1, p2, q2);
+		(n, d) = (d, n - a * d);
+	}
+
+	// The best semiconvergent below the limit, compared with the last convergent.
+	let k = (MAX_DENOMINATOR - q0) / q1;
+	let (p_semi, q_semi) = (p0.saturating_add(k.saturating_mul(p1)), q0.saturating_add(k.saturating_mul(q1)));
+	// Distance from the exact value, scaled by `denominator * q`.
+	let distance = |p: u128, q: u128| p.saturating_mul(denominator).abs_diff(numerator.saturating_mul(q));
+	if distance(p1, q1).saturating_mul(q_semi) <= distance(p_semi, q_semi).saturating_mul(q1) {
+		(p1, q1)
+	} else {
+		(p_semi, q_semi)
+	}
+}
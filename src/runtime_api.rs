@@ -0,0 +1,31 @@
+//! Runtime API of the bets pallet, letting clients read offers and convert odds through the same
+//! rules used on-chain.
+
+use crate::{
+	odds_format::{FormattedOdd, FormattedOdds, OddsFormat},
+	BetIndex, MatchId, Odd, OddsMargin, Prediction,
+};
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait BetsApi<AccountId, Balance> where AccountId: Codec, Balance: Codec {
+		/// The odds offered by `bookmaker` on a match in the requested format, `None` if there is no offer.
+		fn formatted_odds(id_match: MatchId, bookmaker: AccountId, format: OddsFormat) -> Option<FormattedOdds>;
+
+		/// Convert an odd from any format to the decimal odd accepted by `set_odds`.
+		fn parse_odd(odd: FormattedOdd) -> Option<Odd>;
+
+		/// Net loss of the `bookmaker` on each outcome of its odds on a match.
+		fn exposure(id_match: MatchId, bookmaker: AccountId) -> Vec<(Prediction, Balance)>;
+
+		/// Implied probability sum of each market of the `bookmaker` odds on a match, `None` if there is no offer.
+		fn odds_margin(id_match: MatchId, bookmaker: AccountId) -> Option<OddsMargin>;
+
+		/// Live implied odd of each outcome of the parimutuel pool of a match, `None` if it has no stake.
+		fn pool_odds(id_match: MatchId) -> Vec<(Prediction, Option<Odd>)>;
+
+		/// The amount paid to the bettor accepting the cash-out of an open bet, `None` if not offered.
+		fn cash_out(id_bet: BetIndex) -> Option<Balance>;
+	}
+}
@@ -8,7 +8,7 @@ use frame_support::{
 	assert_ok,
 };
 use mock::{
-	new_test_ext, acc_pub, Balances, Bets, Origin, Test, Timestamp,
+	new_test_ext, acc_pub, any_odds, even_odds, football_category, Balances, Bets, OddsDeposit, Origin, System, Test, Timestamp, ORACLE, SECOND_ORACLE,
 };
 
 #[test]
@@ -26,40 +26,1500 @@ fn initial_state() {
 fn end_to_end_three_bets_works() {
 	new_test_ext().execute_with(|| {
 		let id_match: MatchId = (1,23);
-		let odds = Odds {
-			homewin: (2,00),
-			awaywin: (2,00),
-			draw: (2,00),
-			under: (2,00),
-			over: (2,00),
-		};
+		let odds = even_odds();
 		assert_eq!(Balances::total_issuance(), 500);
 		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
 		assert_eq!(Matches::<Test>::contains_key(id_match), true);
 		let now_plus_five_sec: u64 = Timestamp::get().saturating_add(5000);
-		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(1)), id_match, now_plus_five_sec));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, now_plus_five_sec, None));
 
 		let match_created = Bets::matches(id_match).unwrap();
 		assert_eq!(match_created.timestamp_start, now_plus_five_sec);
 		
-		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 40));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 40, any_odds()));
 		assert_eq!(Bets::bets_count(), 1);
-		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Draw, 40));
-		assert_eq!(Bets::bets_count(), 2);
-		assert_noop!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Awaywin, 30), Error::<Test>::OddsAccountInsufficientBalance);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Draw, 40, any_odds()));
 		assert_eq!(Bets::bets_count(), 2);
-		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Awaywin, 20));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Awaywin, 30, any_odds()));
 		assert_eq!(Bets::bets_count(), 3);
-		assert_eq!(Balances::free_balance(acc_pub(1)), 0);
-		assert_ok!(Bets::set_random_match_result(Origin::signed(acc_pub(5)), id_match));
+		// The stakes of a balanced book cover any result.
+		assert_eq!(Balances::free_balance(acc_pub(1)), 100);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 110);
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::set_random_match_result(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_ok!(Bets::set_random_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match));
+		assert_noop!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0), Error::<Test>::DisputePeriodNotOver);
+		Timestamp::set_timestamp(7000);
 		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0));
 		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 1));
 		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 2));
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 0);
+		assert_eq!(Balances::total_issuance(), 500);
 		// assert_eq!(Balances::free_balance(acc_pub(1)) > 100, true);
 		// assert_eq!(Balances::free_balance(acc_pub(2))+Balances::free_balance(acc_pub(3))+Balances::free_balance(acc_pub(4)) < 300, true);
 	});
 }
 
+#[test]
+fn oracle_origin_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,24);
+		let odds = even_odds();
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_noop!(Bets::set_match_start(Origin::signed(acc_pub(1)), id_match, 5000, None), Error::<Test>::NotOracle);
+		assert_noop!(Bets::add_oracle(Origin::signed(acc_pub(1)), acc_pub(1)), sp_runtime::DispatchError::BadOrigin);
+		assert_noop!(Bets::add_oracle(Origin::root(), acc_pub(ORACLE)), Error::<Test>::OracleAlreadyRegistered);
+		assert_ok!(Bets::add_oracle(Origin::root(), acc_pub(1)));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(1)), id_match, 5000, None));
+		assert_ok!(Bets::remove_oracle(Origin::root(), acc_pub(1)));
+		assert_noop!(Bets::remove_oracle(Origin::root(), acc_pub(1)), Error::<Test>::OracleNotRegistered);
+		Timestamp::set_timestamp(6000);
+		assert_noop!(Bets::set_match_result(Origin::signed(acc_pub(1)), id_match, 1, 0), Error::<Test>::NotOracle);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0));
+	});
+}
+
+#[test]
+fn match_result_quorum_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,25);
+		let odds = even_odds();
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		Timestamp::set_timestamp(6000);
+
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0));
+		System::assert_has_event(mock::Event::Bets(Event::MatchResultSubmitted(id_match, acc_pub(ORACLE), 1, 0)));
+		assert_noop!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0), Error::<Test>::ResultAlreadySubmitted);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 2, 0));
+		System::assert_has_event(mock::Event::Bets(Event::MatchResultConflict(id_match, acc_pub(SECOND_ORACLE))));
+		assert_eq!(Bets::matches(id_match).unwrap().status, MatchStatus::Open);
+
+		assert_ok!(Bets::add_oracle(Origin::root(), acc_pub(7)));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(7)), id_match, 1, 0));
+		System::assert_has_event(mock::Event::Bets(Event::MatchResultAgreed(id_match, 1, 0)));
+		let closed_match = Bets::matches(id_match).unwrap();
+		assert_eq!(closed_match.status, MatchStatus::Closed);
+		assert_eq!((closed_match.home_score, closed_match.away_score), (1, 0));
+		assert_eq!(ResultSubmissions::<Test>::iter_prefix(id_match).count(), 0);
+	});
+}
+
+#[test]
+fn match_result_vote_replacement_works() {
+	new_test_ext().execute_with(|| {
+		let split_match: MatchId = (1,61);
+		let revoked_match: MatchId = (1,62);
+		for id_match in [split_match, revoked_match] {
+			assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+			assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		}
+		Timestamp::set_timestamp(6000);
+
+		// A split vote is not stuck: an oracle replaces its own vote.
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), split_match, 1, 0));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), split_match, 2, 0));
+		assert_eq!(Bets::matches(split_match).unwrap().status, MatchStatus::Open);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), split_match, 1, 0));
+		System::assert_has_event(mock::Event::Bets(Event::MatchResultAgreed(split_match, 1, 0)));
+		assert_eq!(Bets::matches(split_match).unwrap().status, MatchStatus::Closed);
+
+		// The vote of a removed oracle no longer counts toward the quorum.
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), revoked_match, 1, 0));
+		assert_ok!(Bets::remove_oracle(Origin::root(), acc_pub(ORACLE)));
+		assert_ok!(Bets::add_oracle(Origin::root(), acc_pub(7)));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(7)), revoked_match, 1, 0));
+		assert_eq!(Bets::matches(revoked_match).unwrap().status, MatchStatus::Open);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), revoked_match, 1, 0));
+		assert_eq!(Bets::matches(revoked_match).unwrap().status, MatchStatus::Closed);
+	});
+}
+
+#[test]
+fn dispute_match_result_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,26);
+		let odds = even_odds();
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+		assert_noop!(Bets::dispute_match_result(Origin::signed(acc_pub(3)), id_match), Error::<Test>::MatchNotClosed);
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 1, 0));
+		assert_eq!(Bets::matches(id_match).unwrap().dispute_end, 7000);
+
+		// A rejected dispute slashes the challenger bond.
+		assert_ok!(Bets::dispute_match_result(Origin::signed(acc_pub(3)), id_match));
+		assert_eq!(Balances::reserved_balance(acc_pub(3)), 10);
+		assert_noop!(Bets::dispute_match_result(Origin::signed(acc_pub(4)), id_match), Error::<Test>::MatchDisputed);
+		assert_noop!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0), Error::<Test>::MatchDisputed);
+		assert_noop!(Bets::resolve_dispute(Origin::signed(acc_pub(5)), id_match, None), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(Bets::resolve_dispute(Origin::root(), id_match, None));
+		assert_eq!(Balances::reserved_balance(acc_pub(3)), 0);
+		assert_eq!(Balances::free_balance(acc_pub(3)), 90);
+		assert_eq!(Balances::total_issuance(), 490);
+
+		// An upheld dispute replaces the result, returns the bond and restarts the dispute period.
+		Timestamp::set_timestamp(6500);
+		assert_ok!(Bets::dispute_match_result(Origin::signed(acc_pub(4)), id_match));
+		assert_ok!(Bets::resolve_dispute(Origin::root(), id_match, Some((0, 1))));
+		assert_eq!(Balances::free_balance(acc_pub(4)), 100);
+		assert_eq!(Bets::matches(id_match).unwrap().dispute_end, 7500);
+		assert_noop!(Bets::resolve_dispute(Origin::root(), id_match, None), Error::<Test>::DisputeNotExists);
+
+		Timestamp::set_timestamp(7000);
+		assert_noop!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0), Error::<Test>::DisputePeriodNotOver);
+		Timestamp::set_timestamp(7500);
+		assert_noop!(Bets::dispute_match_result(Origin::signed(acc_pub(3)), id_match), Error::<Test>::DisputePeriodOver);
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Lost);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 110);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 90);
+	});
+}
+
+#[test]
+fn cancelled_match_voids_bets() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,27);
+		let odds = even_odds();
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Under, 20, any_odds()));
+
+		assert_ok!(Bets::postpone_match(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_noop!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0), Error::<Test>::MatchPostponed);
+		assert_noop!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()), Error::<Test>::MatchNotOpen);
+		assert_ok!(Bets::reschedule_match(Origin::signed(acc_pub(ORACLE)), id_match, 6000));
+		assert_eq!(Bets::matches(id_match).unwrap().status, MatchStatus::Open);
+		assert_noop!(Bets::reschedule_match(Origin::signed(acc_pub(ORACLE)), id_match, 7000), Error::<Test>::MatchNotPostponed);
+
+		assert_ok!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_noop!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), id_match), Error::<Test>::MatchFinalised);
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0));
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 1));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Void);
+		assert_eq!(Bets::bets(1).unwrap().status, BetStatus::Void);
+		for account in 1..=3 {
+			assert_eq!(Balances::free_balance(acc_pub(account)), 100);
+			assert_eq!(Balances::reserved_balance(acc_pub(account)), 0);
+		}
+	});
+}
+
+#[test]
+fn bet_indexes_work() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,28);
+		let odds = even_odds();
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(2)), id_match, odds));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(2), Prediction::Draw, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Over, 10, any_odds()));
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Over, 0, any_odds()),
+			Error::<Test>::ZeroStake
+		);
+
+		assert_eq!(Bets::match_bets(id_match), vec![0, 1, 2]);
+		let mut account_bets = Bets::account_open_bets(&acc_pub(3));
+		account_bets.sort();
+		assert_eq!(account_bets, vec![0, 1]);
+		let mut bookmaker_bets = Bets::bookmaker_open_bets(&acc_pub(1));
+		bookmaker_bets.sort();
+		assert_eq!(bookmaker_bets, vec![0, 2]);
+
+		assert_ok!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0));
+		assert_eq!(Bets::account_open_bets(&acc_pub(3)), vec![1]);
+		assert_eq!(Bets::bookmaker_open_bets(&acc_pub(1)), vec![2]);
+		assert_eq!(Bets::match_bet_count(id_match), 3);
+	});
+}
+
+#[test]
+fn settle_match_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,29);
+		let other_match: MatchId = (1,30);
+		let odds = even_odds();
+		for selected_match in [id_match, other_match] {
+			assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), selected_match, odds));
+			assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), selected_match, 5000, None));
+		}
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Awaywin, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), other_match, acc_pub(1), Prediction::Over, 10, any_odds()));
+		assert_noop!(Bets::settle_match(Origin::signed(acc_pub(5)), id_match, 10), Error::<Test>::MatchOpen);
+
+		// Bulk settlement resumes from the stored cursor.
+		assert_ok!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 1));
+		assert_ok!(Bets::settle_match(Origin::signed(acc_pub(5)), id_match, 2));
+		assert_eq!(Bets::settlement_cursor(id_match), 2);
+		assert_eq!(Bets::bets(2).unwrap().status, BetStatus::Open);
+		assert_ok!(Bets::settle_match(Origin::signed(acc_pub(5)), id_match, 2));
+		System::assert_last_event(mock::Event::Bets(Event::MatchFullySettled(id_match)));
+		assert_eq!(Bets::bets(2).unwrap().status, BetStatus::Void);
+		assert_eq!(PendingSettlements::<Test>::contains_key(id_match), false);
+
+		// Spare block weight settles the remaining matches.
+		assert_ok!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), other_match));
+		Bets::on_idle(1, 1_000_000_000);
+		System::assert_last_event(mock::Event::Bets(Event::MatchFullySettled(other_match)));
+		assert_eq!(Bets::bets(3).unwrap().status, BetStatus::Void);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 0);
+	});
+}
+
+#[test]
+fn idle_settlement_queue_works() {
+	new_test_ext().execute_with(|| {
+		let disputable_match: MatchId = (1,63);
+		let cancelled_match: MatchId = (1,64);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), disputable_match, even_odds()));
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(2)), cancelled_match, even_odds()));
+		for id_match in [disputable_match, cancelled_match] {
+			assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		}
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), disputable_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), cancelled_match, acc_pub(2), Prediction::Homewin, 10, any_odds()));
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), disputable_match, 1, 0));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), disputable_match, 1, 0));
+		assert_ok!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), cancelled_match));
+
+		// The match in its dispute period is skipped, the other one settled, and the queue visited to the end.
+		Bets::on_idle(1, 1_000_000_000);
+		System::assert_has_event(mock::Event::Bets(Event::MatchFullySettled(cancelled_match)));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Open);
+		assert_eq!(Bets::bets(1).unwrap().status, BetStatus::Void);
+		assert!(PendingSettlements::<Test>::contains_key(disputable_match));
+		assert_eq!(Bets::settlement_queue_cursor(), None);
+
+		// Without weight for a bet, the match is left for the next block.
+		Timestamp::set_timestamp(7000);
+		Bets::on_idle(1, 0);
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Open);
+		Bets::on_idle(1, 1_000_000_000);
+		System::assert_last_event(mock::Event::Bets(Event::MatchFullySettled(disputable_match)));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Won);
+		assert!(!PendingSettlements::<Test>::contains_key(disputable_match));
+	});
+}
+
+#[test]
+fn over_under_line_works() {
+	new_test_ext().execute_with(|| {
+		let half_line_match: MatchId = (1,31);
+		let whole_line_match: MatchId = (1,32);
+		let mut odds = even_odds();
+		odds.line = 27;
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), half_line_match, odds), Error::<Test>::InvalidGoalLine);
+		odds.line = 25;
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), half_line_match, odds));
+		odds.line = 30;
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), whole_line_match, odds));
+		for selected_match in [half_line_match, whole_line_match] {
+			assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), selected_match, 5000, None));
+		}
+		// The bettor expecting the 3-goal line does not get the 2.5 one at the same price.
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(2)), half_line_match, acc_pub(1), Prediction::Over, 10, any_odds()),
+			Error::<Test>::OddsChanged
+		);
+		assert_noop!(
+			Bets::place_bet_best(Origin::signed(acc_pub(2)), half_line_match, Prediction::Over, 10, Odd::one(), 30),
+			Error::<Test>::NoOddsAvailable
+		);
+		let half_line = OddsGuard::MinOdd(Odd::one(), 25);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), half_line_match, acc_pub(1), Prediction::Over, 10, half_line));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), half_line_match, acc_pub(1), Prediction::Under, 10, half_line));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), whole_line_match, acc_pub(1), Prediction::Over, 10, any_odds()));
+		assert_eq!(Bets::bets(0).unwrap().line, 25);
+		assert_eq!(Bets::bets(2).unwrap().line, 30);
+		Timestamp::set_timestamp(6000);
+		for selected_match in [half_line_match, whole_line_match] {
+			assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), selected_match, 2, 1));
+			assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), selected_match, 2, 1));
+		}
+		Timestamp::set_timestamp(7000);
+		for id_bet in 0..3 {
+			assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), id_bet));
+		}
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Won);
+		assert_eq!(Bets::bets(1).unwrap().status, BetStatus::Lost);
+		assert_eq!(Bets::bets(2).unwrap().status, BetStatus::Void);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 100);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 110);
+		assert_eq!(Balances::free_balance(acc_pub(3)), 90);
+		assert_eq!(Balances::free_balance(acc_pub(4)), 100);
+	});
+}
+
+#[test]
+fn match_expected_end_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,33);
+		let odds = even_odds();
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, Some(2000)));
+		assert_eq!(Bets::matches(id_match).unwrap().timestamp_end, 7000);
+
+		Timestamp::set_timestamp(6000);
+		assert_noop!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0), Error::<Test>::MatchNotFinished);
+		assert_ok!(Bets::mark_match_finished(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0));
+
+		// A rescheduled match keeps its expected duration.
+		assert_ok!(Bets::postpone_match(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_ok!(Bets::reschedule_match(Origin::signed(acc_pub(ORACLE)), id_match, 10000));
+		assert_eq!(Bets::matches(id_match).unwrap().timestamp_end, 12000);
+	});
+}
+
+#[test]
+fn category_registry_works() {
+	new_test_ext().execute_with(|| {
+		let tennis: MatchCategoryId = 2;
+		let id_match: MatchId = (tennis,1);
+		let mut odds = even_odds();
+		odds.line = 225;
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds), Error::<Test>::CategoryNotExists);
+
+		let mut category = football_category();
+		category.name = b"Tennis Open".to_vec().try_into().unwrap();
+		category.sport = Sport::Tennis;
+		category.duration = 3000;
+		assert_noop!(Bets::set_category(Origin::signed(acc_pub(1)), tennis, category.clone()), sp_runtime::DispatchError::BadOrigin);
+		assert_noop!(Bets::set_category(Origin::root(), tennis, category.clone()), Error::<Test>::InvalidCategoryMarkets);
+		category.markets = vec![Prediction::Homewin, Prediction::Awaywin, Prediction::Under, Prediction::Over].try_into().unwrap();
+		category.oracles = vec![acc_pub(SECOND_ORACLE)].try_into().unwrap();
+		assert_ok!(Bets::set_category(Origin::root(), tennis, category));
+
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_noop!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None), Error::<Test>::NotCategoryOracle);
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 5000, None));
+		assert_eq!(Bets::matches(id_match).unwrap().timestamp_end, 8000);
+		assert_noop!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()), Error::<Test>::MarketNotAllowed);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+
+		assert_ok!(Bets::set_category_enabled(Origin::root(), tennis, false));
+		assert_noop!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()), Error::<Test>::CategoryDisabled);
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(3)), id_match, odds), Error::<Test>::CategoryDisabled);
+		assert_noop!(Bets::set_category_enabled(Origin::root(), 3, true), Error::<Test>::CategoryNotExists);
+	});
+}
+
+#[test]
+fn fixed_point_odds_work() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,34);
+		let mut odds = even_odds();
+		odds.draw = Odd::one();
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds), Error::<Test>::OddOutOfBound);
+		odds.draw = Odd::saturating_from_rational(199, 100);
+		odds.homewin = Odd::saturating_from_rational(25, 10);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+
+		assert_eq!(Bets::winnable_amount(odds.homewin, 10), 15);
+		assert_eq!(Bets::winnable_amount(odds.draw, 100), 99);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Draw, 50, any_odds()));
+		assert_eq!(Bets::bets(0).unwrap().odd, odds.homewin);
+		// Stakes plus the worst-case loss, on the draw: 49 winnable minus the 10 lost on Homewin.
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 10 + 50 + 39);
+	});
+}
+
+#[test]
+fn odds_migration_works() {
+	new_test_ext().execute_with(|| {
+		let odds_id = ((1,35), acc_pub(1));
+		// The encoding of the deployed layouts: five (integer part, percent) odds, and a bet without line.
+		let old_odds = ((2u32, 50u8), (1u32, 5u8), (3u32, 0u8), (1u32, 90u8), (1u32, 90u8));
+		frame_support::storage::unhashed::put(&pallet::Odds::<Test>::hashed_key_for(&odds_id), &old_odds);
+		let old_bet = (acc_pub(2), odds_id.clone(), Prediction::Homewin, (2u32, 50u8), 10u64, BetStatus::Open);
+		frame_support::storage::unhashed::put(&pallet::Bets::<Test>::hashed_key_for(0), &old_bet);
+		let old_match = (MatchStatus::Closed, 2u32, 1u32, 5000u64);
+		frame_support::storage::unhashed::put(&pallet::Matches::<Test>::hashed_key_for(odds_id.0), &old_match);
+		StorageVersion::new(0).put::<Bets>();
+
+		migrations::v1::migrate::<Test>();
+		assert_eq!(Bets::on_chain_storage_version(), 1);
+		// Odds and bets are left in the version 1 layout, for the next migrations.
+		let odds: migrations::v4::OldOdds =
+			frame_support::storage::unhashed::get(&pallet::Odds::<Test>::hashed_key_for(&odds_id)).unwrap();
+		assert_eq!(odds.homewin, Odd::saturating_from_rational(250, 100));
+		assert_eq!(odds.awaywin, Odd::saturating_from_rational(105, 100));
+		assert_eq!(odds.draw, Odd::saturating_from_integer(3));
+		assert_eq!(odds.line, 30);
+		let bet: migrations::v3::OldBet<AccountIdOf<Test>, u64, OddsId<Test>> =
+			frame_support::storage::unhashed::get(&pallet::Bets::<Test>::hashed_key_for(0)).unwrap();
+		assert_eq!(bet.odd, Odd::saturating_from_rational(250, 100));
+		assert_eq!(bet.line, 30);
+		assert_eq!(bet.amount, 10);
+		assert_eq!(bet.status, BetStatus::Open);
+		// Bets on matches closed before the upgrade can be settled.
+		let selected_match = Bets::matches(odds_id.0).unwrap();
+		assert_eq!((selected_match.status, selected_match.home_score, selected_match.away_score), (MatchStatus::Closed, 2, 1));
+		assert_eq!(selected_match.dispute_end, 0);
+		// The expected end of the match is derived from the default duration.
+		assert_eq!(selected_match.timestamp_end, 6000);
+		assert!(!selected_match.finished_early);
+	});
+}
+
+#[test]
+fn odds_formats_work() {
+	use odds_format::{format_odd, parse_odd, FormattedOdd, OddsFormat};
+	new_test_ext().execute_with(|| {
+		let odd = Odd::saturating_from_rational(25, 10);
+		assert_eq!(format_odd(odd, OddsFormat::Decimal), Some(FormattedOdd::Decimal(odd)));
+		assert_eq!(format_odd(odd, OddsFormat::Fractional), Some(FormattedOdd::Fractional(3, 2)));
+		assert_eq!(format_odd(odd, OddsFormat::American), Some(FormattedOdd::American(150)));
+		assert_eq!(parse_odd(FormattedOdd::Fractional(3, 2)), Some(odd));
+		assert_eq!(parse_odd(FormattedOdd::American(150)), Some(odd));
+
+		// Odds below 2 are negative in the American format.
+		let odd = Odd::saturating_from_rational(15, 10);
+		assert_eq!(format_odd(odd, OddsFormat::Fractional), Some(FormattedOdd::Fractional(1, 2)));
+		assert_eq!(format_odd(odd, OddsFormat::American), Some(FormattedOdd::American(-200)));
+		assert_eq!(parse_odd(FormattedOdd::American(-200)), Some(odd));
+
+		// Rounding to the closest fraction and to the nearest integer.
+		let odd = Odd::saturating_from_rational(4, 3);
+		assert_eq!(format_odd(odd, OddsFormat::Fractional), Some(FormattedOdd::Fractional(1, 3)));
+		assert_eq!(format_odd(odd, OddsFormat::American), Some(FormattedOdd::American(-300)));
+		let odd = Odd::saturating_from_rational(31415, 10000);
+		assert_eq!(format_odd(odd, OddsFormat::Fractional), Some(FormattedOdd::Fractional(2028, 947)));
+		assert_eq!(format_odd(odd, OddsFormat::American), Some(FormattedOdd::American(214)));
+
+		// Invalid odds.
+		assert_eq!(format_odd(Odd::one(), OddsFormat::American), None);
+		assert_eq!(parse_odd(FormattedOdd::American(50)), None);
+		assert_eq!(parse_odd(FormattedOdd::Fractional(1, 0)), None);
+		assert_eq!(parse_odd(FormattedOdd::Fractional(0, 1)), None);
+		assert_eq!(parse_odd(FormattedOdd::Decimal(Odd::one())), None);
+
+		let id_match: MatchId = (1,36);
+		assert_eq!(Bets::formatted_odds(id_match, acc_pub(1), OddsFormat::American), None);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		let formatted = Bets::formatted_odds(id_match, acc_pub(1), OddsFormat::Fractional).unwrap();
+		assert_eq!(formatted.homewin, FormattedOdd::Fractional(1, 1));
+		assert_eq!(formatted.line, 30);
+	});
+}
+
+#[test]
+fn bet_limits_work() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,37);
+		let limits = BetLimits { max_stake: Some(20), max_outcome_liability: Some(30), max_match_liability: Some(40) };
+		assert_noop!(Bets::set_bet_limits(Origin::signed(acc_pub(1)), id_match, limits), Error::<Test>::OddsNotExist);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_ok!(Bets::set_bet_limits(Origin::signed(acc_pub(1)), id_match, limits));
+		System::assert_has_event(mock::Event::Bets(Event::BetLimitsSet((id_match, acc_pub(1)))));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 21, any_odds()),
+			Error::<Test>::StakeAboveLimit
+		);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 20, any_odds()));
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Homewin, 11, any_odds()),
+			Error::<Test>::OutcomeLiabilityExceeded
+		);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Draw, 5, any_odds()));
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Over, 16, any_odds()),
+			Error::<Test>::MatchLiabilityExceeded
+		);
+		assert_eq!(Bets::exposure((id_match, acc_pub(1)), Prediction::Homewin), 30);
+		assert_eq!(Bets::match_exposure(id_match, &acc_pub(1)), 25);
+
+		// Settled bets no longer count towards the limits.
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 1));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 1, 1));
+		Timestamp::set_timestamp(7000);
+		assert_ok!(Bets::settle_match(Origin::signed(acc_pub(4)), id_match, 10));
+		assert_eq!(Bets::match_exposure(id_match, &acc_pub(1)), 0);
+	});
+}
+
+#[test]
+fn net_exposure_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,38);
+		let id_odds = (id_match, acc_pub(1));
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 60, any_odds()));
+		assert_eq!(Bets::collateral(&id_odds), 120);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 40);
+		assert_eq!(Balances::reserved_balance(acc_pub(2)), 0);
+		// A bet on the opposite outcome releases collateral.
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Awaywin, 50, any_odds()));
+		assert_eq!(Bets::match_exposure(id_match, &acc_pub(1)), 10);
+		assert_eq!(Bets::collateral(&id_odds), 120);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 90);
+		assert_eq!(
+			Bets::exposures(id_match, acc_pub(1)),
+			vec![
+				(Prediction::Homewin, 10),
+				(Prediction::Awaywin, 0),
+				(Prediction::Draw, 0),
+				(Prediction::Under, 0),
+				(Prediction::Over, 0),
+			]
+		);
+		// The over/under market adds its own worst case.
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Over, 100, any_odds()),
+			Error::<Test>::OddsAccountInsufficientBalance
+		);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Over, 20, any_odds()));
+		assert_eq!(Bets::match_exposure(id_match, &acc_pub(1)), 30);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 70);
+		let mut odds = even_odds();
+		odds.line = 25;
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds), Error::<Test>::GoalLineLocked);
+
+		// Winners settled first are paid from the stakes of the losers.
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 2, 2));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 2, 2));
+		Timestamp::set_timestamp(7000);
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 2));
+		assert_eq!(Balances::free_balance(acc_pub(4)), 120);
+		assert_eq!(Bets::collateral(&id_odds), 120);
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0));
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 1));
+		assert_eq!(Bets::collateral(&id_odds), 0);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 190);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 0);
+		assert_eq!(Bets::exposures(id_match, acc_pub(1)).iter().all(|(_, loss)| *loss == 0), true);
+		assert_eq!(Bets::goal_line(&id_odds), None);
+	});
+}
+
+#[test]
+fn stakes_migration_works() {
+	new_test_ext().execute_with(|| {
+		let id_odds = ((1,39), acc_pub(1));
+		let bet = migrations::v3::OldBet {
+			owner: acc_pub(2),
+			id_odds: id_odds.clone(),
+			prediction: Prediction::Homewin,
+			odd: Odd::saturating_from_integer(2),
+			line: 30,
+			amount: 10u64,
+			status: BetStatus::Open,
+		};
+		frame_support::storage::unhashed::put(&pallet::Bets::<Test>::hashed_key_for(0), &bet);
+		let unstaked_odds = ((1,59), acc_pub(1));
+		let unstaked_bet = migrations::v3::OldBet { owner: acc_pub(3), id_odds: unstaked_odds.clone(), ..bet };
+		frame_support::storage::unhashed::put(&pallet::Bets::<Test>::hashed_key_for(1), &unstaked_bet);
+		assert_ok!(Balances::reserve(&acc_pub(2), 10));
+		assert_ok!(Balances::reserve(&acc_pub(1), 20));
+		StorageVersion::new(1).put::<Bets>();
+
+		migrations::v2::migrate::<Test>();
+		assert_eq!(Bets::on_chain_storage_version(), 2);
+		assert_eq!(Balances::reserved_balance(acc_pub(2)), 0);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 20);
+		assert_eq!(Bets::stakes(&id_odds, Prediction::Homewin), 10);
+		assert_eq!(Bets::exposure(&id_odds, Prediction::Homewin), 10);
+		assert_eq!(Bets::collateral(&id_odds), 20);
+
+		// The bet without a reserved stake is voided and left out of the book.
+		let unstaked_bet: migrations::v3::OldBet<AccountIdOf<Test>, u64, OddsId<Test>> =
+			frame_support::storage::unhashed::get(&pallet::Bets::<Test>::hashed_key_for(1)).unwrap();
+		assert_eq!(unstaked_bet.status, BetStatus::Void);
+		assert_eq!(Bets::stakes(&unstaked_odds, Prediction::Homewin), 0);
+		assert_eq!(Bets::exposure(&unstaked_odds, Prediction::Homewin), 0);
+		assert_eq!(Bets::collateral(&unstaked_odds), 0);
+	});
+}
+
+#[test]
+fn legacy_bets_settlement_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,60);
+		let odds_id = (id_match, acc_pub(1));
+		// An open bet of the deployed layout on a match closed before the upgrade: the bettor holds
+		// the stake and the bookmaker the winnable amount.
+		let old_odds = ((2u32, 50u8), (2u32, 0u8), (2u32, 0u8), (2u32, 0u8), (2u32, 0u8));
+		frame_support::storage::unhashed::put(&pallet::Odds::<Test>::hashed_key_for(&odds_id), &old_odds);
+		let old_bet = (acc_pub(2), odds_id.clone(), Prediction::Homewin, (2u32, 50u8), 10u64, BetStatus::Open);
+		frame_support::storage::unhashed::put(&pallet::Bets::<Test>::hashed_key_for(0), &old_bet);
+		pallet::BetCount::<Test>::put(1);
+		let old_match = (MatchStatus::Closed, 2u32, 1u32, 0u64);
+		frame_support::storage::unhashed::put(&pallet::Matches::<Test>::hashed_key_for(id_match), &old_match);
+		assert_ok!(Balances::reserve(&acc_pub(2), 10));
+		assert_ok!(Balances::reserve(&acc_pub(1), 15));
+		StorageVersion::new(0).put::<Bets>();
+
+		<Bets as frame_support::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(Bets::on_chain_storage_version(), 6);
+		assert_eq!(Bets::match_bets(id_match), vec![0]);
+		assert_eq!(Bets::account_open_bets(&acc_pub(2)), vec![0]);
+		assert_eq!(Bets::bookmaker_open_bets(&acc_pub(1)), vec![0]);
+
+		assert_ok!(Bets::settle_match(Origin::signed(acc_pub(3)), id_match, 10));
+		System::assert_last_event(mock::Event::Bets(Event::MatchFullySettled(id_match)));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Won);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 115);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 0);
+		assert!(Bets::account_open_bets(&acc_pub(2)).is_empty());
+		assert!(Bets::bookmaker_open_bets(&acc_pub(1)).is_empty());
+	});
+}
+
+#[test]
+fn odds_versioning_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,40);
+		let id_odds = (id_match, acc_pub(1));
+		let mut odds = even_odds();
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		System::assert_last_event(mock::Event::Bets(Event::OddsCreated(id_odds.clone())));
+		assert_eq!(Bets::odds_version(&id_odds), 1);
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Awaywin, 10, OddsGuard::Version(1)));
+		odds.homewin = Odd::saturating_from_rational(15, 10);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		System::assert_last_event(mock::Event::Bets(Event::OddsUpdated(id_odds.clone(), 2)));
+		// The first version stays in the history for its open bet.
+		assert_eq!(Bets::odds_history(&id_odds, 1), Some(even_odds()));
+		assert_eq!(Bets::odds_history(&id_odds, 2), Some(odds));
+
+		// The bettor saw the first version.
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, OddsGuard::Version(1)),
+			Error::<Test>::OddsChanged
+		);
+		assert_noop!(
+			Bets::place_bet(
+				Origin::signed(acc_pub(2)),
+				id_match,
+				acc_pub(1),
+				Prediction::Homewin,
+				10,
+				OddsGuard::MinOdd(Odd::saturating_from_integer(2), 30)
+			),
+			Error::<Test>::OddsChanged
+		);
+		assert_ok!(Bets::place_bet(
+			Origin::signed(acc_pub(2)),
+			id_match,
+			acc_pub(1),
+			Prediction::Homewin,
+			10,
+			OddsGuard::Version(2)
+		));
+		assert_ok!(Bets::place_bet(
+			Origin::signed(acc_pub(2)),
+			id_match,
+			acc_pub(1),
+			Prediction::Draw,
+			10,
+			OddsGuard::MinOdd(Odd::saturating_from_integer(2), 30)
+		));
+		assert_eq!(Bets::bets(1).unwrap().odds_version, 2);
+		assert_eq!(Bets::bets(1).unwrap().odd, Odd::saturating_from_rational(15, 10));
+
+		// A version leaves the history once no longer live nor accepted by open bets.
+		let mut next_odds = odds;
+		next_odds.draw = Odd::saturating_from_integer(3);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, next_odds));
+		assert_eq!(Bets::odds_history(&id_odds, 2), Some(odds));
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_eq!(Bets::odds_history(&id_odds, 3), None);
+		assert_ok!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0));
+		assert_eq!(Bets::odds_history(&id_odds, 1), None);
+		assert_eq!(Bets::odds_history(&id_odds, 4), Some(odds));
+	});
+}
+
+#[test]
+fn odds_version_migration_works() {
+	new_test_ext().execute_with(|| {
+		let id_odds = ((1,41), acc_pub(1));
+		let old_bet = migrations::v3::OldBet {
+			owner: acc_pub(2),
+			id_odds: id_odds.clone(),
+			prediction: Prediction::Draw,
+			odd: Odd::saturating_from_integer(3),
+			line: 30,
+			amount: 10u64,
+			status: BetStatus::Won,
+		};
+		frame_support::storage::unhashed::put(&pallet::Bets::<Test>::hashed_key_for(0), &old_bet);
+		let open_bet = migrations::v3::OldBet { status: BetStatus::Open, ..old_bet };
+		frame_support::storage::unhashed::put(&pallet::Bets::<Test>::hashed_key_for(1), &open_bet);
+		let two = Odd::saturating_from_integer(2);
+		let old_odds = migrations::v4::OldOdds { homewin: two, awaywin: two, draw: two, under: two, over: two, line: 30 };
+		frame_support::storage::unhashed::put(&pallet::Odds::<Test>::hashed_key_for(&id_odds), &old_odds);
+		StorageVersion::new(2).put::<Bets>();
+
+		migrations::v3::migrate::<Test>();
+		assert_eq!(Bets::on_chain_storage_version(), 3);
+		let bet: migrations::v6::OldBet<AccountIdOf<Test>, u64, OddsId<Test>> =
+			frame_support::storage::unhashed::get(&pallet::Bets::<Test>::hashed_key_for(0)).unwrap();
+		assert_eq!(bet.odds_version, 0);
+		assert_eq!(bet.status, BetStatus::Won);
+		assert_eq!(bet.odd, Odd::saturating_from_integer(3));
+		// The live odds are version 0, kept in the history for the open bet.
+		let history: migrations::v4::OldOdds =
+			frame_support::storage::unhashed::get(&pallet::OddsHistory::<Test>::hashed_key_for(&id_odds, 0)).unwrap();
+		assert_eq!(history.draw, two);
+		assert_eq!(pallet::OddsVersionBets::<Test>::get(&id_odds, 0), 1);
+	});
+}
+
+#[test]
+fn withdraw_and_suspend_odds_work() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,42);
+		let id_odds = (id_match, acc_pub(1));
+		assert_noop!(Bets::suspend_odds(Origin::signed(acc_pub(1)), id_match, None), Error::<Test>::OddsNotExist);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+
+		// Pull just the Draw price.
+		assert_ok!(Bets::suspend_odds(Origin::signed(acc_pub(1)), id_match, Some(Prediction::Draw)));
+		System::assert_last_event(mock::Event::Bets(Event::OddsSuspended(id_odds.clone(), Some(Prediction::Draw))));
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()),
+			Error::<Test>::OddsSuspended
+		);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+
+		// The whole offer is suspended, the Draw stays suspended once it is resumed.
+		assert_ok!(Bets::suspend_odds(Origin::signed(acc_pub(1)), id_match, None));
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()),
+			Error::<Test>::OddsSuspended
+		);
+		assert_ok!(Bets::resume_odds(Origin::signed(acc_pub(1)), id_match, None));
+		System::assert_last_event(mock::Event::Bets(Event::OddsResumed(id_odds.clone(), None)));
+		assert_eq!(Bets::suspension(&id_odds).is_suspended(&Prediction::Draw), true);
+		assert_ok!(Bets::resume_odds(Origin::signed(acc_pub(1)), id_match, Some(Prediction::Draw)));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()));
+
+		// Withdrawn odds take no more bets, existing ones are settled.
+		assert_ok!(Bets::set_cash_out_margin(Origin::signed(acc_pub(1)), id_match, Some(Permill::from_percent(5))));
+		assert_ok!(Bets::set_bet_cancellation(Origin::signed(acc_pub(1)), id_match, false));
+		assert_ok!(Bets::withdraw_odds(Origin::signed(acc_pub(1)), id_match));
+		System::assert_last_event(mock::Event::Bets(Event::OddsWithdrawn(id_odds.clone())));
+		// The settings of the offer go with it.
+		assert_eq!(Bets::cash_out_margin(&id_odds), None);
+		assert!(!NonCancellableOdds::<Test>::contains_key(&id_odds));
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()),
+			Error::<Test>::OddsNotExist
+		);
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()), Error::<Test>::OddsWithdrawn);
+		assert_noop!(Bets::withdraw_odds(Origin::signed(acc_pub(1)), id_match), Error::<Test>::OddsNotExist);
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 1, 0));
+		Timestamp::set_timestamp(7000);
+		assert_ok!(Bets::settle_match(Origin::signed(acc_pub(5)), id_match, 10));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Won);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 110);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 100);
+	});
+}
+
+#[test]
+fn odds_expiry_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,43);
+		let id_odds = (id_match, acc_pub(1));
+		Timestamp::set_timestamp(1000);
+		let mut odds = even_odds();
+		odds.valid_until = Some(1000);
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds), Error::<Test>::OddsExpired);
+		odds.valid_until = Some(3000);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		odds.valid_until = Some(2000);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(2)), id_match, odds));
+		odds.valid_until = Some(2500);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(3)), id_match, odds));
+		assert_eq!(Bets::expiry_cursor(), Some(1));
+		assert_eq!(Bets::expiring_odds(2).into_inner(), vec![(id_match, acc_pub(2)), (id_match, acc_pub(3))]);
+		assert_eq!(Bets::expiring_odds(3).into_inner(), vec![id_odds.clone()]);
+		// A full bucket leaves the others available.
+		odds.valid_until = Some(2100);
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds), Error::<Test>::TooManyExpiringOdds);
+		// A new version without validity leaves its bucket.
+		odds.valid_until = None;
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(2)), id_match, odds));
+		assert_eq!(Bets::expiring_odds(2).into_inner(), vec![(id_match, acc_pub(3))]);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Over, 10, any_odds()));
+
+		// The odds expire at once, the bucket is pruned once entirely in the past.
+		Timestamp::set_timestamp(3000);
+		assert_noop!(
+			Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Homewin, 10, any_odds()),
+			Error::<Test>::OddsExpired
+		);
+		Bets::on_idle(1, 1_000_000_000);
+		System::assert_has_event(mock::Event::Bets(Event::OddsExpired((id_match, acc_pub(3)))));
+		assert_eq!(Bets::expiry_cursor(), Some(3));
+		assert!(Bets::odds(&id_odds).is_some());
+		assert_ok!(Bets::set_cash_out_margin(Origin::signed(acc_pub(1)), id_match, Some(Permill::from_percent(5))));
+		assert_ok!(Bets::set_bet_cancellation(Origin::signed(acc_pub(1)), id_match, false));
+		Timestamp::set_timestamp(4000);
+		Bets::on_idle(1, 1_000_000_000);
+		System::assert_has_event(mock::Event::Bets(Event::OddsExpired(id_odds.clone())));
+		assert_eq!(Bets::odds(&id_odds), None);
+		assert_eq!(Bets::cash_out_margin(&id_odds), None);
+		assert!(!NonCancellableOdds::<Test>::contains_key(&id_odds));
+		assert_eq!(Bets::expiring_odds(3).len(), 0);
+		assert_eq!(Bets::expiry_cursor(), Some(4));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Open);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(2), Prediction::Homewin, 10, any_odds()));
+		// The open over bet keeps the line locked once the odds expired.
+		assert_eq!(Bets::goal_line(&id_odds), Some(30));
+		odds.line = 25;
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds), Error::<Test>::GoalLineLocked);
+		odds.line = 30;
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+	});
+}
+
+#[test]
+fn odds_deposit_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,58);
+		OddsDeposit::set(5);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_eq!(Bets::odds_deposit(&(id_match, acc_pub(1))), 5);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 5);
+		// A bookmaker unable to pay the deposit takes no place on the match.
+		assert_ok!(Balances::reserve(&acc_pub(2), 98));
+		assert_noop!(
+			Bets::set_odds(Origin::signed(acc_pub(2)), id_match, even_odds()),
+			Error::<Test>::OddsAccountInsufficientBalance
+		);
+
+		// The deposit is released with the odds, withdrawn or expired.
+		assert_ok!(Bets::withdraw_odds(Origin::signed(acc_pub(1)), id_match));
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 0);
+		let mut odds = even_odds();
+		odds.valid_until = Some(2000);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(3)), id_match, odds));
+		assert_eq!(Balances::reserved_balance(acc_pub(3)), 5);
+		Timestamp::set_timestamp(3000);
+		Bets::on_idle(1, 1_000_000_000);
+		assert_eq!(Bets::match_bookmakers(id_match).len(), 0);
+		assert_eq!(Balances::reserved_balance(acc_pub(3)), 0);
+	});
+}
+
+#[test]
+fn odds_margin_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,44);
+		// Every outcome at 4.0 guarantees an arbitrage on the result market.
+		let mut odds = even_odds();
+		odds.homewin = Odd::saturating_from_integer(4);
+		odds.awaywin = Odd::saturating_from_integer(4);
+		odds.draw = Odd::saturating_from_integer(4);
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds), Error::<Test>::OverroundTooLow);
+		let mut odds = even_odds();
+		odds.homewin = Odd::saturating_from_rational(11, 10);
+		odds.awaywin = Odd::saturating_from_rational(11, 10);
+		odds.draw = Odd::saturating_from_rational(11, 10);
+		assert_noop!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds), Error::<Test>::OverroundTooHigh);
+
+		// A fair book, with no margin, is accepted.
+		let mut odds = even_odds();
+		odds.homewin = Odd::saturating_from_rational(25, 10);
+		odds.awaywin = Odd::saturating_from_rational(25, 10);
+		odds.draw = Odd::saturating_from_integer(5);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		let margin = Bets::odds_margin(id_match, acc_pub(1)).unwrap();
+		assert_eq!(margin.result, Some(FixedU128::one()));
+		assert_eq!(margin.total_goals, Some(FixedU128::one()));
+		odds.draw = Odd::saturating_from_integer(4);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_eq!(Bets::odds_margin(id_match, acc_pub(1)).unwrap().result, Some(FixedU128::saturating_from_rational(105, 100)));
+
+		// Markets not allowed by the category are not counted.
+		let mut category = football_category();
+		category.markets = vec![Prediction::Homewin, Prediction::Awaywin, Prediction::Under].try_into().unwrap();
+		let margin = Bets::margin(&even_odds(), &category.markets);
+		assert_eq!(margin.result, Some(FixedU128::one()));
+		assert_eq!(margin.total_goals, None);
+		assert_eq!(Bets::odds_margin(id_match, acc_pub(2)), None);
+	});
+}
+
+#[test]
+fn vaults_work() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,45);
+		let vault = Bets::vault_account(0);
+		assert_ok!(Bets::create_vault(Origin::signed(acc_pub(1))));
+		assert_noop!(Bets::set_vault_odds(Origin::signed(acc_pub(2)), 0, id_match, even_odds()), Error::<Test>::NotVaultManager);
+		assert_noop!(Bets::deposit_to_vault(Origin::signed(acc_pub(2)), 1, 50), Error::<Test>::VaultNotExists);
+		assert_noop!(Bets::deposit_to_vault(Origin::signed(acc_pub(2)), 0, 0), Error::<Test>::ZeroShares);
+		assert_ok!(Bets::deposit_to_vault(Origin::signed(acc_pub(2)), 0, 50));
+		assert_ok!(Bets::deposit_to_vault(Origin::signed(acc_pub(3)), 0, 50));
+		assert_eq!(Bets::vault_shares(0, acc_pub(3)), 50);
+		assert_eq!(Bets::vaults(0).unwrap().shares, 100);
+
+		// Bets on the vault odds are collateralised from the vault.
+		assert_ok!(Bets::set_vault_odds(Origin::signed(acc_pub(1)), 0, id_match, even_odds()));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, vault.clone(), Prediction::Homewin, 40, any_odds()));
+		assert_eq!(Balances::reserved_balance(&vault), 80);
+		assert_eq!(Bets::vault_value(0), 100);
+
+		// The manager acts on the vault odds as their bookmaker.
+		let suspend = Box::new(pallet::Call::suspend_odds { id_match, prediction: None });
+		assert_noop!(Bets::vault_call(Origin::signed(acc_pub(2)), 0, suspend.clone()), Error::<Test>::NotVaultManager);
+		let bet = Box::new(pallet::Call::place_bet {
+			id_match, odds_owner: acc_pub(4), prediction: Prediction::Homewin, amount: 10, expected: any_odds(),
+		});
+		assert_noop!(Bets::vault_call(Origin::signed(acc_pub(1)), 0, bet), Error::<Test>::NotVaultCall);
+		assert_ok!(Bets::vault_call(Origin::signed(acc_pub(1)), 0, suspend));
+		assert_eq!(Bets::suspension(&(id_match, vault.clone())).all, true);
+		assert_ok!(Bets::vault_call(Origin::signed(acc_pub(1)), 0, Box::new(pallet::Call::resume_odds { id_match, prediction: None })));
+
+		// Only the funds not exposed to the open bets can be withdrawn.
+		assert_noop!(Bets::withdraw_from_vault(Origin::signed(acc_pub(2)), 0, 60), Error::<Test>::InsufficientShares);
+		assert_ok!(Bets::withdraw_from_vault(Origin::signed(acc_pub(2)), 0, 50));
+		assert_eq!(Balances::free_balance(acc_pub(2)), 100);
+		assert_noop!(Bets::withdraw_from_vault(Origin::signed(acc_pub(3)), 0, 50), Error::<Test>::VaultInsufficientFreeBalance);
+
+		// The shares are not priced while the match is played.
+		Timestamp::set_timestamp(5000);
+		assert_noop!(Bets::deposit_to_vault(Origin::signed(acc_pub(2)), 0, 45), Error::<Test>::VaultSettling);
+		assert_noop!(Bets::withdraw_from_vault(Origin::signed(acc_pub(3)), 0, 10), Error::<Test>::VaultSettling);
+
+		// The losing bet raises the share value.
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 0, 1));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 0, 1));
+		// The shares are not priced on the known result before the bet is settled.
+		assert_noop!(Bets::deposit_to_vault(Origin::signed(acc_pub(2)), 0, 45), Error::<Test>::VaultSettling);
+		assert_noop!(Bets::withdraw_from_vault(Origin::signed(acc_pub(3)), 0, 10), Error::<Test>::VaultSettling);
+		Timestamp::set_timestamp(7000);
+		assert_ok!(Bets::settle_bet(Origin::signed(acc_pub(5)), 0));
+		assert_eq!(Bets::vault_settling(0), false);
+		assert_eq!(Bets::vault_value(0), 90);
+		assert_ok!(Bets::deposit_to_vault(Origin::signed(acc_pub(2)), 0, 45));
+		assert_eq!(Bets::vault_shares(0, acc_pub(2)), 25);
+		assert_ok!(Bets::withdraw_from_vault(Origin::signed(acc_pub(3)), 0, 50));
+		assert_eq!(Balances::free_balance(acc_pub(3)), 140);
+		assert_eq!(Bets::vault_shares(0, acc_pub(3)), 0);
+		assert_eq!(Bets::vault_value(0), 45);
+
+		// Funds held by a vault before its first shares are burnt, the first depositor buying at par.
+		let other_vault = Bets::vault_account(1);
+		assert_ok!(Bets::create_vault(Origin::signed(acc_pub(1))));
+		assert_ok!(Balances::transfer(Origin::signed(acc_pub(5)), other_vault.clone(), 10));
+		assert_ok!(Bets::deposit_to_vault(Origin::signed(acc_pub(3)), 1, 20));
+		assert_eq!(Bets::vault_shares(1, acc_pub(3)), 20);
+		assert_eq!(Bets::vaults(1).unwrap().shares, 30);
+		// A deposit priced below a share by funds sent to the vault is rejected.
+		assert_ok!(Balances::transfer(Origin::signed(acc_pub(5)), other_vault.clone(), 60));
+		assert_noop!(Bets::deposit_to_vault(Origin::signed(acc_pub(2)), 1, 2), Error::<Test>::ZeroShares);
+
+		// The odds deposit is not part of the share value, and vault odds take no accumulators.
+		let (first_match, second_match): (MatchId, MatchId) = ((1,65), (1,66));
+		OddsDeposit::set(5);
+		for selected_match in [first_match, second_match] {
+			assert_ok!(Bets::set_vault_odds(Origin::signed(acc_pub(1)), 1, selected_match, even_odds()));
+			assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), selected_match, 9000, None));
+		}
+		assert_eq!(Balances::reserved_balance(&other_vault), 10);
+		assert_eq!(Bets::vault_value(1), 80);
+		let selections: BoundedVec<_, _> =
+			vec![(first_match, Prediction::Homewin), (second_match, Prediction::Homewin)].try_into().unwrap();
+		assert_noop!(
+			Bets::place_accumulator(Origin::signed(acc_pub(4)), other_vault, selections, 10, Odd::one()),
+			Error::<Test>::VaultAccumulator
+		);
+	});
+}
+
+#[test]
+fn exchange_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,46);
+		let two = Odd::saturating_from_integer(2);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_noop!(
+			Bets::place_order(Origin::signed(acc_pub(2)), id_match, Prediction::Homewin, OrderSide::Back, two, 0, 30),
+			Error::<Test>::MatchNotOpen
+		);
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+
+		// Unmatched orders wait on the book, up to its capacity.
+		assert_ok!(Bets::place_order(Origin::signed(acc_pub(2)), id_match, Prediction::Homewin, OrderSide::Back, two, 0, 30));
+		assert_ok!(Bets::place_order(
+			Origin::signed(acc_pub(2)), id_match, Prediction::Homewin, OrderSide::Back, Odd::saturating_from_rational(25, 10), 0, 10
+		));
+		assert_eq!(Balances::reserved_balance(acc_pub(2)), 40);
+		assert_noop!(
+			Bets::place_order(Origin::signed(acc_pub(3)), id_match, Prediction::Homewin, OrderSide::Back, two, 0, 10),
+			Error::<Test>::OrderBookFull
+		);
+
+		// A lay order at 2.2 is partially matched at 2.0, the best back odd.
+		assert_ok!(Bets::place_order(
+			Origin::signed(acc_pub(3)), id_match, Prediction::Homewin, OrderSide::Lay, Odd::saturating_from_rational(22, 10), 0, 40
+		));
+		let bet = Bets::matched_bets(0).unwrap();
+		assert_eq!((bet.backer, bet.layer, bet.odd, bet.amount), (acc_pub(2), acc_pub(3), two, 30));
+		assert_eq!(Bets::orders(2).unwrap().amount, 10);
+		assert_eq!(Balances::reserved_balance(acc_pub(3)), 42);
+		assert_ok!(Bets::place_order(
+			Origin::signed(acc_pub(4)), id_match, Prediction::Homewin, OrderSide::Back, Odd::saturating_from_rational(21, 10), 0, 20
+		));
+		assert_eq!(Bets::matched_bets(1).unwrap().odd, Odd::saturating_from_rational(22, 10));
+		assert_eq!(Bets::orders(2), None);
+		assert_eq!(Bets::order_book(id_match, (Prediction::Homewin, OrderSide::Back)).len(), 2);
+
+		// Only the owner cancels an order before the match starts.
+		assert_noop!(Bets::cancel_order(Origin::signed(acc_pub(3)), 3), Error::<Test>::NotOrderOwner);
+		assert_ok!(Bets::cancel_order(Origin::signed(acc_pub(2)), 1));
+		assert_eq!(Balances::reserved_balance(acc_pub(2)), 30);
+
+		// An order is not matched with the orders of its owner.
+		assert_ok!(Bets::place_order(
+			Origin::signed(acc_pub(4)), id_match, Prediction::Homewin, OrderSide::Lay, Odd::saturating_from_rational(21, 10), 0, 10
+		));
+		assert_eq!(Bets::matched_bets(2), None);
+		assert_eq!(Balances::reserved_balance(acc_pub(4)), 31);
+		assert_ok!(Bets::cancel_order(Origin::signed(acc_pub(4)), 4));
+		assert_noop!(Bets::settle_matched_bet(Origin::signed(acc_pub(5)), 0), Error::<Test>::MatchOpen);
+
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::cancel_order(Origin::signed(acc_pub(3)), 3));
+		assert_eq!(Balances::reserved_balance(acc_pub(4)), 10);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 1, 0));
+		Timestamp::set_timestamp(7000);
+		assert_ok!(Bets::settle_matched_bet(Origin::signed(acc_pub(5)), 0));
+		assert_ok!(Bets::settle_matched_bet(Origin::signed(acc_pub(5)), 1));
+		assert_noop!(Bets::settle_matched_bet(Origin::signed(acc_pub(5)), 1), Error::<Test>::BetSettled);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 130);
+		assert_eq!(Balances::free_balance(acc_pub(4)), 112);
+		assert_eq!(Balances::free_balance(acc_pub(3)), 58);
+		assert_eq!(Balances::reserved_balance(acc_pub(3)), 0);
+	});
+}
+
+#[test]
+fn order_lapse_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,67);
+		let two = Odd::saturating_from_integer(2);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_noop!(
+			Bets::place_order(Origin::signed(acc_pub(2)), id_match, Prediction::Homewin, OrderSide::Back, two, 0, 4),
+			Error::<Test>::OrderBelowMinimum
+		);
+		assert_ok!(Bets::place_order(Origin::signed(acc_pub(2)), id_match, Prediction::Homewin, OrderSide::Back, two, 0, 10));
+		assert_ok!(Bets::place_order(
+			Origin::signed(acc_pub(3)), id_match, Prediction::Homewin, OrderSide::Lay, Odd::saturating_from_rational(15, 10), 0, 10
+		));
+		assert_eq!(Bets::lapsing_books(5).into_inner(), vec![id_match]);
+		assert_eq!(Bets::lapse_cursor(), Some(0));
+
+		// The orders of a rescheduled match are kept for its new start.
+		assert_ok!(Bets::postpone_match(Origin::signed(acc_pub(ORACLE)), id_match));
+		assert_ok!(Bets::reschedule_match(Origin::signed(acc_pub(ORACLE)), id_match, 8000));
+		Timestamp::set_timestamp(6000);
+		Bets::on_idle(1, 1_000_000_000);
+		assert!(Bets::orders(0).is_some());
+		assert_eq!(Bets::lapsing_books(5).len(), 0);
+		assert_eq!(Bets::lapsing_books(8).into_inner(), vec![id_match]);
+		assert_eq!(Bets::lapse_cursor(), Some(6));
+
+		// Once the match started, its orders leave the book and their reserves are released.
+		Timestamp::set_timestamp(9000);
+		Bets::on_idle(1, 1_000_000_000);
+		System::assert_has_event(mock::Event::Bets(Event::OrderCancelled(0)));
+		System::assert_has_event(mock::Event::Bets(Event::OrderCancelled(1)));
+		assert_eq!((Bets::orders(0), Bets::orders(1)), (None, None));
+		assert_eq!(Bets::order_book(id_match, (Prediction::Homewin, OrderSide::Back)).len(), 0);
+		assert_eq!(Balances::reserved_balance(acc_pub(2)), 0);
+		assert_eq!(Balances::reserved_balance(acc_pub(3)), 0);
+		assert_eq!(Bets::lapsing_books(8).len(), 0);
+		assert_eq!(Bets::lapse_cursor(), Some(9));
+	});
+}
+
+#[test]
+fn best_odds_routing_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,47);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_noop!(
+			Bets::place_bet_best(Origin::signed(acc_pub(3)), id_match, Prediction::Homewin, 50, Odd::one(), 30),
+			Error::<Test>::MatchNotOpen
+		);
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		let mut odds = even_odds();
+		odds.homewin = Odd::saturating_from_rational(25, 10);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(2)), id_match, odds));
+		let limits = BetLimits { max_stake: Some(20), max_outcome_liability: None, max_match_liability: None };
+		assert_ok!(Bets::set_bet_limits(Origin::signed(acc_pub(2)), id_match, limits));
+		odds.homewin = Odd::saturating_from_integer(3);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(4)), id_match, odds));
+		// Beyond the bookmakers of the match, odds are offered but not routed to.
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(5)), id_match, even_odds()));
+		assert_eq!(Bets::match_bookmakers(id_match).into_inner(), vec![acc_pub(1), acc_pub(2), acc_pub(4)]);
+		// The best bookmaker can only cover a small stake.
+		assert_ok!(Balances::reserve(&acc_pub(4), 90));
+
+		assert_noop!(
+			Bets::place_bet_best(Origin::signed(acc_pub(3)), id_match, Prediction::Homewin, 50, Odd::saturating_from_rational(31, 10), 30),
+			Error::<Test>::NoOddsAvailable
+		);
+		assert_ok!(Bets::place_bet_best(Origin::signed(acc_pub(3)), id_match, Prediction::Homewin, 50, Odd::one(), 30));
+		let fills: Vec<_> = (0..3).map(|id_bet| {
+			let bet = Bets::bets(id_bet).unwrap();
+			(bet.id_odds.1, bet.odd, bet.amount)
+		}).collect();
+		assert_eq!(fills, vec![
+			(acc_pub(4), Odd::saturating_from_integer(3), 5),
+			(acc_pub(2), Odd::saturating_from_rational(25, 10), 20),
+			(acc_pub(1), Odd::saturating_from_integer(2), 25),
+		]);
+		assert_eq!(Balances::free_balance(acc_pub(3)), 50);
+		assert_eq!(Balances::free_balance(acc_pub(4)), 0);
+
+		// A withdrawn bookmaker is no longer routed to, and its place goes to the next odds updated.
+		assert_ok!(Bets::withdraw_odds(Origin::signed(acc_pub(1)), id_match));
+		assert_eq!(Bets::match_bookmakers(id_match).len(), 2);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(5)), id_match, even_odds()));
+		assert_eq!(Bets::match_bookmakers(id_match).into_inner(), vec![acc_pub(2), acc_pub(4), acc_pub(5)]);
+	});
+}
+
+#[test]
+fn match_bookmakers_migration_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,48);
+		pallet::Odds::<Test>::insert((id_match, acc_pub(1)), even_odds());
+		pallet::Odds::<Test>::insert((id_match, acc_pub(2)), even_odds());
+		StorageVersion::new(4).put::<Bets>();
+
+		migrations::v5::migrate::<Test>();
+		assert_eq!(Bets::on_chain_storage_version(), 5);
+		let mut bookmakers = Bets::match_bookmakers(id_match).into_inner();
+		bookmakers.sort();
+		let mut expected = vec![acc_pub(1), acc_pub(2)];
+		expected.sort();
+		assert_eq!(bookmakers, expected);
+	});
+}
+
+#[test]
+fn parimutuel_pool_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,49);
+		assert_ok!(Bets::open_pool(Origin::signed(acc_pub(1)), id_match));
+		assert_noop!(Bets::open_pool(Origin::signed(acc_pub(1)), id_match), Error::<Test>::PoolAlreadyExists);
+		assert_noop!(
+			Bets::place_pool_bet(Origin::signed(acc_pub(2)), id_match, Prediction::Homewin, 30),
+			Error::<Test>::MatchNotOpen
+		);
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_noop!(
+			Bets::place_pool_bet(Origin::signed(acc_pub(2)), id_match, Prediction::Over, 30),
+			Error::<Test>::PoolMarketNotAllowed
+		);
+		assert_noop!(
+			Bets::place_pool_bet(Origin::signed(acc_pub(2)), id_match, Prediction::Homewin, 0),
+			Error::<Test>::ZeroStake
+		);
+		assert_ok!(Bets::place_pool_bet(Origin::signed(acc_pub(2)), id_match, Prediction::Homewin, 30));
+		assert_ok!(Bets::place_pool_bet(Origin::signed(acc_pub(3)), id_match, Prediction::Homewin, 10));
+		assert_ok!(Bets::place_pool_bet(Origin::signed(acc_pub(4)), id_match, Prediction::Awaywin, 60));
+		assert_eq!(Balances::free_balance(Bets::pool_account()), 100);
+
+		// The pool, minus the 10% take, is shared by the stakes on each outcome.
+		assert_eq!(
+			Bets::pool_odds(id_match),
+			vec![
+				(Prediction::Homewin, Some(Odd::saturating_from_rational(225, 100))),
+				(Prediction::Awaywin, Some(Odd::saturating_from_rational(15, 10))),
+				(Prediction::Draw, None),
+			]
+		);
+		assert_noop!(Bets::claim_pool(Origin::signed(acc_pub(2)), id_match), Error::<Test>::MatchOpen);
+
+		Timestamp::set_timestamp(6000);
+		assert_noop!(
+			Bets::place_pool_bet(Origin::signed(acc_pub(2)), id_match, Prediction::Draw, 10),
+			Error::<Test>::MatchStarted
+		);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), id_match, 1, 0));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), id_match, 1, 0));
+		Timestamp::set_timestamp(7000);
+		assert_ok!(Bets::claim_pool(Origin::signed(acc_pub(2)), id_match));
+		assert_ok!(Bets::claim_pool(Origin::signed(acc_pub(3)), id_match));
+		assert_ok!(Bets::claim_pool(Origin::signed(acc_pub(4)), id_match));
+		assert_noop!(Bets::claim_pool(Origin::signed(acc_pub(2)), id_match), Error::<Test>::NothingToClaim);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 137);
+		assert_eq!(Balances::free_balance(acc_pub(3)), 112);
+		assert_eq!(Balances::free_balance(acc_pub(4)), 40);
+		// The take leaves the pallet account.
+		assert_eq!(Balances::free_balance(Bets::pool_account()), 0);
+	});
+}
+
+#[test]
+fn accumulator_works() {
+	new_test_ext().execute_with(|| {
+		let (first_match, second_match): (MatchId, MatchId) = ((1,50), (1,51));
+		let mut odds = even_odds();
+		odds.draw = Odd::saturating_from_integer(3);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), first_match, even_odds()));
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), second_match, odds));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), first_match, 5000, None));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), second_match, 5000, None));
+
+		let selections = |legs: Vec<(MatchId, Prediction)>| -> BoundedVec<_, _> { legs.try_into().unwrap() };
+		assert_noop!(
+			Bets::place_accumulator(
+				Origin::signed(acc_pub(2)), acc_pub(1), selections(vec![(first_match, Prediction::Homewin)]), 10, Odd::one()
+			),
+			Error::<Test>::AccumulatorTooShort
+		);
+		assert_noop!(
+			Bets::place_accumulator(
+				Origin::signed(acc_pub(2)),
+				acc_pub(1),
+				selections(vec![(first_match, Prediction::Homewin), (first_match, Prediction::Draw)]),
+				10,
+				Odd::one()
+			),
+			Error::<Test>::DuplicateLeg
+		);
+		let legs = selections(vec![(first_match, Prediction::Homewin), (second_match, Prediction::Draw)]);
+		let limits = BetLimits { max_stake: None, max_outcome_liability: None, max_match_liability: Some(50) };
+		assert_ok!(Bets::set_bet_limits(Origin::signed(acc_pub(1)), second_match, limits));
+		assert_noop!(
+			Bets::place_accumulator(Origin::signed(acc_pub(2)), acc_pub(1), legs.clone(), 10, Odd::one()),
+			Error::<Test>::LiabilityLimitedOdds
+		);
+		assert_ok!(Bets::set_bet_limits(Origin::signed(acc_pub(1)), second_match, BetLimits::default()));
+		assert_noop!(
+			Bets::place_accumulator(Origin::signed(acc_pub(2)), acc_pub(1), legs.clone(), 0, Odd::one()),
+			Error::<Test>::ZeroStake
+		);
+		assert_noop!(
+			Bets::place_accumulator(Origin::signed(acc_pub(2)), acc_pub(1), legs.clone(), 10, Odd::saturating_from_integer(7)),
+			Error::<Test>::OddsChanged
+		);
+		assert_ok!(Bets::place_accumulator(Origin::signed(acc_pub(2)), acc_pub(1), legs, 10, Odd::saturating_from_integer(6)));
+		assert_eq!(Bets::accumulators(0).unwrap().odd, Odd::saturating_from_integer(6));
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 60);
+
+		// Settlement waits for every match, a void leg drops out.
+		Timestamp::set_timestamp(6000);
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), first_match, 1, 0));
+		assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), first_match, 1, 0));
+		Timestamp::set_timestamp(7000);
+		assert_noop!(Bets::settle_accumulator(Origin::signed(acc_pub(5)), 0), Error::<Test>::MatchOpen);
+		assert_ok!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), second_match));
+		assert_ok!(Bets::settle_accumulator(Origin::signed(acc_pub(5)), 0));
+		assert_eq!(Bets::accumulators(0).unwrap().status, BetStatus::Won);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 110);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 90);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 0);
+	});
+}
+
+#[test]
+fn system_bet_works() {
+	new_test_ext().execute_with(|| {
+		let matches: Vec<MatchId> = vec![(1,52), (1,53), (1,54)];
+		for id_match in matches.iter() {
+			assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), *id_match, even_odds()));
+			assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), *id_match, 5000, None));
+		}
+		let selections: BoundedVec<_, _> =
+			matches.iter().map(|id_match| (*id_match, Prediction::Homewin)).collect::<Vec<_>>().try_into().unwrap();
+		let folds = |sizes: Vec<u32>| -> BoundedVec<_, _> { sizes.try_into().unwrap() };
+		assert_noop!(
+			Bets::place_system_bet(Origin::signed(acc_pub(2)), acc_pub(1), selections.clone(), folds(vec![2]), 0),
+			Error::<Test>::ZeroStake
+		);
+		assert_noop!(
+			Bets::place_system_bet(Origin::signed(acc_pub(2)), acc_pub(1), selections.clone(), folds(vec![1, 2]), 5),
+			Error::<Test>::InvalidFoldSize
+		);
+		assert_noop!(
+			Bets::place_system_bet(Origin::signed(acc_pub(2)), acc_pub(1), selections.clone(), folds(vec![3, 2]), 5),
+			Error::<Test>::InvalidFoldSize
+		);
+		// A Trixie expands to 4 lines.
+		assert_noop!(
+			Bets::place_system_bet(Origin::signed(acc_pub(2)), acc_pub(1), selections.clone(), folds(vec![2, 3]), 5),
+			Error::<Test>::TooManySystemLines
+		);
+		assert_ok!(Bets::place_system_bet(Origin::signed(acc_pub(2)), acc_pub(1), selections, folds(vec![2]), 5));
+		assert_eq!(Bets::system_bets(0).unwrap().lines.into_inner(), vec![0, 1, 2]);
+		let line = Bets::accumulators(1).unwrap();
+		assert_eq!(line.legs.iter().map(|leg| leg.id_match).collect::<Vec<_>>(), vec![(1,52), (1,54)]);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 85);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 60);
+
+		// Each line is settled on its own.
+		Timestamp::set_timestamp(6000);
+		for id_match in matches[..2].iter() {
+			assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(ORACLE)), *id_match, 1, 0));
+			assert_ok!(Bets::set_match_result(Origin::signed(acc_pub(SECOND_ORACLE)), *id_match, 1, 0));
+		}
+		assert_ok!(Bets::cancel_match(Origin::signed(acc_pub(ORACLE)), matches[2]));
+		Timestamp::set_timestamp(7000);
+		for id_accumulator in 0..3 {
+			assert_ok!(Bets::settle_accumulator(Origin::signed(acc_pub(5)), id_accumulator));
+		}
+		assert_eq!(Balances::free_balance(acc_pub(2)), 125);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 75);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 0);
+	});
+}
+
+#[test]
+fn cash_out_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,55);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 50, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Awaywin, 50, any_odds()));
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 100);
+		assert_eq!(Bets::cash_out_value(0), None);
+
+		// A cash-out offer on a single bet.
+		assert_noop!(Bets::offer_cash_out(Origin::signed(acc_pub(3)), 0, Some(40)), Error::<Test>::NotBetBookmaker);
+		assert_noop!(Bets::offer_cash_out(Origin::signed(acc_pub(1)), 0, Some(150)), Error::<Test>::CashOutAboveWinnable);
+		assert_ok!(Bets::offer_cash_out(Origin::signed(acc_pub(1)), 0, Some(40)));
+		assert_noop!(Bets::accept_cash_out(Origin::signed(acc_pub(3)), 0, 40), Error::<Test>::NotBetOwner);
+		assert_noop!(Bets::accept_cash_out(Origin::signed(acc_pub(2)), 0, 45), Error::<Test>::CashOutChanged);
+		assert_ok!(Bets::accept_cash_out(Origin::signed(acc_pub(2)), 0, 40));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::CashedOut);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 90);
+		// The reserve covers the bet left alone.
+		assert_eq!(Bets::collateral(&(id_match, acc_pub(1))), 100);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 60);
+
+		// A cash-out priced on the live odds.
+		assert_noop!(Bets::accept_cash_out(Origin::signed(acc_pub(3)), 1, 0), Error::<Test>::NoCashOutOffer);
+		let mut odds = even_odds();
+		odds.awaywin = Odd::saturating_from_rational(25, 10);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, odds));
+		assert_ok!(Bets::set_cash_out_margin(Origin::signed(acc_pub(1)), id_match, Some(Permill::from_percent(10))));
+		assert_eq!(Bets::cash_out_value(1), Some(36));
+		// The live odds no longer price the cash-out once the match starts.
+		Timestamp::set_timestamp(5000);
+		assert_noop!(Bets::accept_cash_out(Origin::signed(acc_pub(3)), 1, 0), Error::<Test>::NoCashOutOffer);
+		Timestamp::set_timestamp(0);
+		assert_ok!(Bets::accept_cash_out(Origin::signed(acc_pub(3)), 1, 36));
+		assert_noop!(Bets::accept_cash_out(Origin::signed(acc_pub(3)), 1, 36), Error::<Test>::BetSettled);
+		assert_eq!(Balances::free_balance(acc_pub(3)), 86);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 124);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 0);
+		assert!(Bets::bookmaker_open_bets(&acc_pub(1)).is_empty());
+	});
+}
+
+#[test]
+fn cancel_bet_works() {
+	new_test_ext().execute_with(|| {
+		let id_match: MatchId = (1,56);
+		assert_ok!(Bets::set_odds(Origin::signed(acc_pub(1)), id_match, even_odds()));
+		assert_ok!(Bets::set_match_start(Origin::signed(acc_pub(ORACLE)), id_match, 5000, None));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(2)), id_match, acc_pub(1), Prediction::Homewin, 50, any_odds()));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(3)), id_match, acc_pub(1), Prediction::Awaywin, 20, any_odds()));
+		assert_eq!(Bets::bets(0).unwrap().placed_at, 1);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 100);
+
+		// The fee goes to the bookmaker, whose reserve only covers the bet left.
+		assert_noop!(Bets::cancel_bet(Origin::signed(acc_pub(3)), 0), Error::<Test>::NotBetOwner);
+		assert_ok!(Bets::cancel_bet(Origin::signed(acc_pub(2)), 0));
+		assert_eq!(Bets::bets(0).unwrap().status, BetStatus::Cancelled);
+		assert_eq!(Balances::free_balance(acc_pub(2)), 95);
+		assert_eq!(Balances::reserved_balance(acc_pub(1)), 40);
+		assert_eq!(Balances::free_balance(acc_pub(1)), 85);
+		assert_noop!(Bets::cancel_bet(Origin::signed(acc_pub(2)), 0), Error::<Test>::BetSettled);
+
+		System::set_block_number(12);
+		assert_noop!(Bets::cancel_bet(Origin::signed(acc_pub(3)), 1), Error::<Test>::CancellationPeriodOver);
+
+		// Bookmakers can opt out of cancellations, for the bets placed from then on.
+		assert_ok!(Bets::set_bet_cancellation(Origin::signed(acc_pub(1)), id_match, false));
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()));
+		assert_noop!(Bets::cancel_bet(Origin::signed(acc_pub(4)), 2), Error::<Test>::CancellationNotAllowed);
+		assert_ok!(Bets::set_bet_cancellation(Origin::signed(acc_pub(1)), id_match, true));
+		assert_noop!(Bets::cancel_bet(Origin::signed(acc_pub(4)), 2), Error::<Test>::CancellationNotAllowed);
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()));
+		assert_ok!(Bets::cancel_bet(Origin::signed(acc_pub(4)), 3));
+		assert_eq!(Balances::free_balance(acc_pub(4)), 89);
+
+		// Never after the match starts.
+		assert_ok!(Bets::place_bet(Origin::signed(acc_pub(4)), id_match, acc_pub(1), Prediction::Draw, 10, any_odds()));
+		Timestamp::set_timestamp(5000);
+		assert_noop!(Bets::cancel_bet(Origin::signed(acc_pub(4)), 4), Error::<Test>::MatchStarted);
+	});
+}
+
+#[test]
+fn placed_at_migration_works() {
+	new_test_ext().execute_with(|| {
+		let old_bet = migrations::v6::OldBet {
+			owner: acc_pub(2),
+			id_odds: ((1,57), acc_pub(1)),
+			prediction: Prediction::Over,
+			odd: Odd::saturating_from_integer(2),
+			line: 25,
+			amount: 10u64,
+			status: BetStatus::Open,
+			odds_version: 3,
+		};
+		frame_support::storage::unhashed::put(&pallet::Bets::<Test>::hashed_key_for(0), &old_bet);
+		StorageVersion::new(5).put::<Bets>();
+
+		migrations::v6::migrate::<Test>();
+		assert_eq!(Bets::on_chain_storage_version(), 6);
+		let bet = Bets::bets(0).unwrap();
+		assert_eq!(bet.odds_version, 3);
+		assert_eq!(bet.placed_at, 0);
+		assert_eq!(bet.cancellable, false);
+		assert_eq!(bet.line, 25);
+	});
+}
+
 #[test]
 fn ocw_test() {
 	new_test_ext_ocw();